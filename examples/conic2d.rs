@@ -0,0 +1,38 @@
+// a conic from its six coefficients, drawn flat with its center, vertices, foci,
+// directrices and asymptotes
+extern crate jive;
+extern crate sdl2;
+
+use std::time::Duration;
+
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use jive::jives::jiveconic::Conic2D;
+use jive::util::bustin::{jive_render_init, render_conic_plot, ConicPlotStyle, ScreenData};
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData::default();
+
+    // Ax^2 + Bxy + Cy^2 + Dx + Ey + F, a tilted hyperbola off the origin
+    let conic = Conic2D::new([1.0, 3.0, -1.0, -2.0, 4.0, -6.0])?;
+    let style = ConicPlotStyle::default();
+
+    let (mut canvas, mut event_pump) = jive_render_init(800, 800);
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        canvas.set_draw_color(Color::RGB(25, 25, 25));
+        canvas.clear();
+        render_conic_plot(&mut canvas, &conic, &screen_data, &style)?;
+        canvas.present();
+        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 30));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// dandelin spheres for a cone cut by a tilted plane
+extern crate jive;
+extern crate sdl2;
+
+use std::time::Duration;
+
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use jive::jives::jivedandelin::DandelinScene;
+use jive::jives::jivesurface::SurfaceKind;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{jive_render_init, render_dandelin_scene, degrees_to_radians, DandelinStyle, ScreenData};
+use jive::util::depth::DepthSorted;
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData::default();
+
+    // same inputs as the README: a cone (steepness, height) and a plane (a, b, c, d)
+    let cone = SurfaceKind::cone(2.0, 9.0)?;
+    let plane = SurfaceKind::plane_with_extent(0.5, 0.0, 1.0, 4.0, 6.0)?;
+    let mut scene = DandelinScene::new(&cone, &plane)?;
+    for sphere in scene.spheres.iter() {
+        println!("sphere at z = {}, radius {}, focus {:?}", sphere.center.e[2], sphere.radius, sphere.focus.e);
+    }
+    let style = DandelinStyle::default();
+
+    let (mut canvas, mut event_pump) = jive_render_init(800, 800);
+    let theta: f32 = degrees_to_radians(&1.0);
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        // painter's order, the near side drawn over the far side
+        let mut frame = DepthSorted::new(&mut canvas);
+        frame.clear(Color::RGB(25, 25, 25))?;
+
+        scene.rotate_roll(theta);
+        render_dandelin_scene(&mut frame, &scene, &screen_data, &style)?;
+
+        frame.present()?;
+        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// the dandelin scene rendered without a window, written out as dandelin.ppm and dandelin.svg
+extern crate jive;
+extern crate sdl2;
+
+use sdl2::pixels::Color;
+
+use jive::jives::jivedandelin::DandelinScene;
+use jive::jives::jivesurface::SurfaceKind;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{render_dandelin_scene, degrees_to_radians, DandelinStyle, ScreenData};
+use jive::util::depth::DepthSorted;
+use jive::util::framebuffer::Framebuffer;
+use jive::util::svg::SvgDocument;
+
+// the same frame whatever it is drawn on
+fn draw<B: RenderBackend>(backend: &mut B, scene: &DandelinScene, screen_data: &ScreenData) -> Result<(), String> {
+    backend.clear(Color::RGB(25, 25, 25))?;
+    render_dandelin_scene(backend, scene, screen_data, &DandelinStyle::default())?;
+    backend.present()
+}
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData::default();
+    let (width, height) = (screen_data.screen_width as u32, screen_data.screen_height as u32);
+
+    let cone = SurfaceKind::cone(2.0, 9.0)?;
+    let plane = SurfaceKind::plane_with_extent(0.5, 0.0, 1.0, 4.0, 6.0)?;
+    let mut scene = DandelinScene::new(&cone, &plane)?;
+    scene.rotate_roll(degrees_to_radians(&-70.0));
+
+    let mut framebuffer = Framebuffer::with_depth(width, height)?;
+    draw(&mut framebuffer, &scene, &screen_data)?;
+    framebuffer.save_ppm("dandelin.ppm")?;
+
+    // no z-buffer in a vector drawing, the shapes go in far to near instead
+    let mut svg = SvgDocument::new(width, height)?;
+    draw(&mut DepthSorted::new(&mut svg), &scene, &screen_data)?;
+    svg.save_svg("dandelin.svg")?;
+
+    println!("wrote dandelin.ppm and dandelin.svg");
+    Ok(())
+}
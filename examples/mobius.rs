@@ -0,0 +1,66 @@
+// a user defined surface going through the same renderer as the built in ones
+extern crate jive;
+extern crate sdl2;
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use jive::jives::jiveparametric::ParametricSurface;
+use jive::jives::jivesurface::JiveSurface;
+use jive::la::linear_algebra::Vec3f;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{jive_render_init, render_jive_wireframe, degrees_to_radians, ScreenData, WireframeOptions};
+use jive::util::depth::DepthSorted;
+
+struct Mobius {
+    radius: f32,
+    half_width: f32,
+}
+
+impl ParametricSurface for Mobius {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        // u goes around the strip, v goes across it
+        let r = self.radius + v * (u / 2.0).cos();
+        Vec3f::from(r * u.cos(), r * u.sin(), v * (u / 2.0).sin())
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        (-self.half_width, self.half_width)
+    }
+    // not periodic in u: the strip comes back flipped, u = 2pi meets u = 0 at -v
+}
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData::default();
+
+    let mut strip = JiveSurface::from_parametric(Mobius{ radius: 0.3, half_width: 0.1 }, 120, 8);
+    let wireframe = WireframeOptions{ u_lines: 60, v_lines: 5, color: Color::RGB(120, 200, 255) };
+
+    let (mut canvas, mut event_pump) = jive_render_init(800, 800);
+    let theta: f32 = degrees_to_radians(&1.0);
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        // painter's order, the near side drawn over the far side
+        let mut frame = DepthSorted::new(&mut canvas);
+        frame.clear(Color::RGB(25, 25, 25))?;
+
+        strip.rotate_roll(theta);
+        render_jive_wireframe(&mut frame, &strip, &screen_data, &wireframe)?;
+
+        frame.present()?;
+        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    }
+
+    Ok(())
+}
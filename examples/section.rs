@@ -0,0 +1,63 @@
+// a cone, a cutting plane and the exact conic where they meet, all in one scene
+extern crate jive;
+extern crate sdl2;
+
+use std::time::Duration;
+
+use sdl2::event::Event;
+use sdl2::pixels::Color;
+
+use jive::jives::jivesection::ConicSection;
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{jive_render_init, render_jive_curve, render_jive_wireframe, degrees_to_radians,
+                         CurveStyle, ScreenData, WireframeOptions};
+use jive::util::depth::DepthSorted;
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData::default();
+
+    let steepness = 2.0;
+    let cone_kind = SurfaceKind::cone(steepness, 8.0)?;
+    let plane_kind = SurfaceKind::plane_with_extent(0.6, 0.0, 1.0, 4.0, 6.0)?;
+    let conic_section = ConicSection::new(&cone_kind, &plane_kind)?;
+    println!("{:?}, eccentricity {}", conic_section.class, conic_section.eccentricity());
+
+    // both nappes, x^2/a^2 + y^2/a^2 = z^2 with a = 1 / steepness
+    let mut cone = JiveSurface::new(SurfaceKind::elliptic_cone(1.0 / steepness, 1.0 / steepness, 8.0)?)?;
+    let mut plane = JiveSurface::new(plane_kind)?;
+    let mut curves = conic_section.curves()?;
+
+    let cone_style = WireframeOptions{ u_lines: 24, v_lines: 8, color: Color::RGB(90, 90, 110) };
+    let plane_style = WireframeOptions{ u_lines: 10, v_lines: 10, color: Color::RGB(70, 110, 70) };
+    let curve_style = CurveStyle{ thickness: 3, color: Color::RGB(255, 200, 60), ..CurveStyle::default() };
+
+    let (mut canvas, mut event_pump) = jive_render_init(800, 800);
+    let theta: f32 = degrees_to_radians(&1.0);
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        // painter's order, the near side drawn over the far side
+        let mut frame = DepthSorted::new(&mut canvas);
+        frame.clear(Color::RGB(25, 25, 25))?;
+
+        cone.rotate_roll(theta);
+        plane.rotate_roll(theta);
+        render_jive_wireframe(&mut frame, &cone, &screen_data, &cone_style)?;
+        render_jive_wireframe(&mut frame, &plane, &screen_data, &plane_style)?;
+        for curve in curves.iter_mut() {
+            curve.rotate_roll(theta);
+            render_jive_curve(&mut frame, curve, &screen_data, &curve_style)?;
+        }
+
+        frame.present()?;
+        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    }
+
+    Ok(())
+}
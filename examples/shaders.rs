@@ -0,0 +1,41 @@
+// the built in shaders, each on its own torus, written out as shaders.ppm
+extern crate jive;
+extern crate sdl2;
+
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::bustin::{render_jive_shaded, ScreenData, ShadingOptions};
+use jive::util::framebuffer::Framebuffer;
+use jive::util::shader::{Checkerboard, HeightMap, Normal, Position, Shader, Solid};
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData{ screen_width: 1200.0, screen_height: 300.0, ..ScreenData::default() };
+    let mut framebuffer = Framebuffer::with_depth(1200, 300)?;
+    framebuffer.clear(Color::RGB(25, 25, 25));
+
+    let checkers = Checkerboard{ a: Color::RGB(230, 230, 230), b: Color::RGB(40, 40, 40), u_squares: 24, v_squares: 8 };
+    // heights along the screen's up, y runs down it
+    let heights = HeightMap::new(Vec3f::from(0.0, -1.0, 0.0), -0.3, 0.3)?;
+    let shaders: Vec<Box<dyn Shader>> = vec![
+        Box::new(Solid(Color::RGB(220, 120, 60))),
+        Box::new(Position),
+        Box::new(Normal),
+        Box::new(checkers),
+        Box::new(heights.clone()),
+        // checkers tinted by the height map
+        Box::new(checkers.multiply(heights)),
+    ];
+    for (i, shader) in shaders.into_iter().enumerate() {
+        let mut torus = JiveSurface::builder(SurfaceKind::torus(6.0, 2.5)?).samples(72, 36).surface_id(i).build()?;
+        torus.surface_shader = Some(shader);
+        torus.rotate_roll(1.1);
+        torus.translate(Vec3f::from(-2.5 + i as f32, 0.0, 0.0));
+        render_jive_shaded(&mut framebuffer, &torus, &screen_data, &ShadingOptions::default())?;
+    }
+
+    framebuffer.save_ppm("shaders.ppm")?;
+    println!("wrote shaders.ppm");
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// the same torus flat, Gouraud and Phong shaded, written out as shading.ppm
+extern crate jive;
+extern crate sdl2;
+
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::bustin::{render_jive_shaded, ScreenData, ShadingOptions};
+use jive::util::framebuffer::Framebuffer;
+use jive::util::lighting::{Light, Material, Shading};
+
+fn main() -> Result<(), String> {
+    let screen_data = ScreenData{ screen_width: 1200.0, screen_height: 400.0, ..ScreenData::default() };
+    let mut framebuffer = Framebuffer::with_depth(1200, 400)?;
+    framebuffer.clear(Color::RGB(25, 25, 25));
+
+    // a key light, a warm point light off to the right and a little ambient
+    let lights = vec![
+        Light::ambient(0.12),
+        Light::directional(Vec3f::from(1.0, 1.0, 1.0), 0.8)?,
+        Light::Point{ position: Vec3f::from(1.5, -0.5, -1.0), color: Color::RGB(255, 180, 120), intensity: 0.4 },
+    ];
+    let colors = [Color::RGB(220, 90, 80), Color::RGB(90, 200, 120), Color::RGB(90, 140, 230)];
+    for (i, shading) in [Shading::Flat, Shading::Gouraud, Shading::Phong].into_iter().enumerate() {
+        let mut torus = JiveSurface::builder(SurfaceKind::torus(6.0, 2.5)?)
+            .samples(36, 18)
+            .material(Material{ specular: 0.6, shininess: 48.0, ..Material::new(colors[i]) })
+            .build()?;
+        torus.rotate_roll(1.1);
+        torus.translate(Vec3f::from(-1.1 + 1.1 * i as f32, 0.0, 0.0));
+        render_jive_shaded(&mut framebuffer, &torus, &screen_data, &ShadingOptions{ shading, lights: lights.clone() })?;
+    }
+
+    framebuffer.save_ppm("shading.ppm")?;
+    println!("wrote shading.ppm");
+    Ok(())
+}
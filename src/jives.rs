@@ -1,2 +1,10 @@
+pub mod jiveconic;
+pub mod jivecurve;
+pub mod jivedandelin;
+pub mod jiveimplicit;
+pub mod jivemesh;
 pub mod jivemodel;
+pub mod jiveparametric;
+pub mod jivequadric;
+pub mod jivesection;
 pub mod jivesurface;
\ No newline at end of file
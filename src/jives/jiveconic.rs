@@ -0,0 +1,266 @@
+/*
+----- The jive conic -----
+A conic in its own plane from the six coefficients Ax^2 + Bxy + Cy^2 + Dx + Ey + F = 0,
+the same [f32; 6] that frdim hands over as conic_coef.
+
+    ----- Removing the rotation -----
+    the quadratic part is the symmetric [[A, B/2], [B/2, C]], its eigenvectors are the
+    principal directions and its eigenvalues l1, l2 what is left of A and C once the Bxy
+    term is rotated away: l1 X^2 + l2 Y^2 + D'X + E'Y + F = 0
+
+    ----- Completing the square -----
+    with both eigenvalues nonzero the center is X = -D'/2l1, Y = -E'/2l2 and the conic is
+    l1 X^2 + l2 Y^2 + F' = 0 around it. With one of them zero it is a parabola (or a pair of
+    parallel lines) and the square is completed in the other direction only
+
+    ----- Properties -----
+    center, vertices, foci, directrices, asymptotes and eccentricity are worked out from
+    the canonical form and given back in the conic's own coordinates
+*/
+
+use std::f32::consts::PI;
+use crate::la::matrix::{Eigen2x2, Mat2x2};
+use crate::jives::jivesection::ConicClass;
+
+// an eigenvalue this small next to the other one counts as zero
+const TOLERANCE: f32 = 1e-4;
+
+/// The line through `point` along the unit vector `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line2 {
+    pub point: [f32; 2],
+    pub direction: [f32; 2],
+}
+
+// the curve in canonical coordinates, X along axes[0] and Y along axes[1] from origin
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Ellipse { a: f32, b: f32 },
+    Hyperbola { a: f32, b: f32 },
+    // X^2 = 4pY, opening along axes[0]
+    Parabola { p: f32 },
+    Lines(Vec<Line2>),
+    Point,
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conic2D {
+    pub coefficients: [f32; 6],
+    pub class: ConicClass,
+    /// unit principal directions: the major (transverse) axis first, or for a
+    /// parabola the direction it opens in
+    pub axes: [[f32; 2]; 2],
+    /// None for parabolas and the shapes without one
+    pub center: Option<[f32; 2]>,
+    pub vertices: Vec<[f32; 2]>,
+    pub foci: Vec<[f32; 2]>,
+    pub directrices: Vec<Line2>,
+    pub asymptotes: Vec<Line2>,
+    pub eccentricity: Option<f32>,
+    origin: [f32; 2],
+    shape: Shape,
+}
+
+impl Conic2D {
+    pub fn new(coefficients: [f32; 6]) -> Result<Conic2D, String> {
+        if coefficients.iter().any(|c| !c.is_finite()) {
+            return Err(format!("conic coefficients must be finite, got {coefficients:?}"));
+        }
+        let [a, b, c, d, e, _] = coefficients;
+        if a == 0.0 && b == 0.0 && c == 0.0 {
+            return Err("A, B and C are all zero, that is a line and not a conic".to_string());
+        }
+
+        let quadratic = Mat2x2::new([a, b / 2.0, b / 2.0, c]);
+        let (l1, l2, v1) = match quadratic.eigen() {
+            Eigen2x2::Real { values, vectors } => (values[0], values[1], vectors[0]),
+            Eigen2x2::Repeated { value, vector, .. } => (value, value, vector),
+            // a symmetric matrix always has real eigenvalues
+            Eigen2x2::Complex { .. } => return Err(format!("no real principal axes for {:?}", quadratic.e)),
+        };
+        let v2 = perpendicular(v1);
+        // the linear part in the rotated frame
+        let (d1, e2) = (d * v1[0] + e * v1[1], d * v2[0] + e * v2[1]);
+
+        let largest = l1.abs().max(l2.abs());
+        let zero1 = l1.abs() <= TOLERANCE * largest;
+        let zero2 = l2.abs() <= TOLERANCE * largest;
+
+        let mut conic = Conic2D{ coefficients, class: ConicClass::Empty, axes: [v1, v2], center: None,
+                                 vertices: Vec::new(), foci: Vec::new(), directrices: Vec::new(),
+                                 asymptotes: Vec::new(), eccentricity: None, origin: [0.0, 0.0], shape: Shape::Empty };
+        if zero1 || zero2 {
+            // (l, along) is the curved direction, across is the one the parabola opens along
+            let (l, along, across, linear_along, linear_across) =
+                if zero2 { (l1, v1, v2, d1, e2) } else { (l2, v2, v1, e2, d1) };
+            conic.parabolic(l, along, across, linear_along, linear_across);
+        } else {
+            conic.central(l1, l2, v1, v2, d1, e2);
+        }
+        Ok(conic)
+    }
+
+    // both eigenvalues nonzero: ellipse, hyperbola or their degenerate cases
+    fn central(&mut self, l1: f32, l2: f32, v1: [f32; 2], v2: [f32; 2], d1: f32, e2: f32) {
+        let (x0, y0) = (-d1 / (2.0 * l1), -e2 / (2.0 * l2));
+        let center = combine([0.0, 0.0], v1, x0, v2, y0);
+        let f = self.coefficients[5];
+        let constant = f + d1 * x0 / 2.0 + e2 * y0 / 2.0;
+        let vanishes = constant.abs() <= TOLERANCE * (f.abs() + (d1 * x0).abs() + (e2 * y0).abs());
+        self.center = Some(center);
+        self.origin = center;
+
+        if l1.signum() == l2.signum() {
+            if vanishes {
+                self.class = ConicClass::Point;
+                self.shape = Shape::Point;
+            } else if constant.signum() == l1.signum() {
+                self.class = ConicClass::Empty;
+                self.center = None;
+            } else {
+                // l1 X^2 + l2 Y^2 = -F', semi axes sqrt(-F'/l)
+                let (s1, s2) = (-constant / l1, -constant / l2);
+                let (a, b, major) = if s1 >= s2 { (s1.sqrt(), s2.sqrt(), v1) } else { (s2.sqrt(), s1.sqrt(), v2) };
+                let minor = perpendicular(major);
+                let focal = (a * a - b * b).max(0.0).sqrt();
+                let eccentricity = focal / a;
+                self.class = ConicClass::Ellipse;
+                self.axes = [major, minor];
+                self.vertices = vec![combine(center, major, a, minor, 0.0), combine(center, major, -a, minor, 0.0)];
+                self.foci = vec![combine(center, major, focal, minor, 0.0), combine(center, major, -focal, minor, 0.0)];
+                if eccentricity > TOLERANCE {
+                    let distance = a / eccentricity;
+                    self.directrices = [distance, -distance].iter()
+                        .map(|&k| Line2{ point: combine(center, major, k, minor, 0.0), direction: minor })
+                        .collect();
+                }
+                self.eccentricity = Some(eccentricity);
+                self.shape = Shape::Ellipse{ a, b };
+            }
+        } else if vanishes {
+            // l1 X^2 + l2 Y^2 = 0 is Y = +-sqrt(-l1/l2) X
+            let slope = (-l1 / l2).sqrt();
+            let lines: Vec<Line2> = [slope, -slope].iter()
+                .map(|&k| Line2{ point: center, direction: unit(combine([0.0, 0.0], v1, 1.0, v2, k)) })
+                .collect();
+            self.class = ConicClass::LinePair;
+            self.shape = Shape::Lines(lines);
+        } else {
+            // the transverse axis is the one where -F'/l is positive
+            let (transverse, a_squared, b_squared) =
+                if -constant / l1 > 0.0 { (v1, -constant / l1, constant / l2) } else { (v2, -constant / l2, constant / l1) };
+            let conjugate = perpendicular(transverse);
+            let (a, b) = (a_squared.sqrt(), b_squared.sqrt());
+            let focal = (a * a + b * b).sqrt();
+            let eccentricity = focal / a;
+            let distance = a / eccentricity;
+            self.class = ConicClass::Hyperbola;
+            self.axes = [transverse, conjugate];
+            self.vertices = vec![combine(center, transverse, a, conjugate, 0.0), combine(center, transverse, -a, conjugate, 0.0)];
+            self.foci = vec![combine(center, transverse, focal, conjugate, 0.0), combine(center, transverse, -focal, conjugate, 0.0)];
+            self.directrices = [distance, -distance].iter()
+                .map(|&k| Line2{ point: combine(center, transverse, k, conjugate, 0.0), direction: conjugate })
+                .collect();
+            self.asymptotes = [b, -b].iter()
+                .map(|&k| Line2{ point: center, direction: unit(combine([0.0, 0.0], transverse, a, conjugate, k)) })
+                .collect();
+            self.eccentricity = Some(eccentricity);
+            self.shape = Shape::Hyperbola{ a, b };
+        }
+    }
+
+    // one eigenvalue zero: l X^2 + D X + E Y + F = 0, X along `along`, Y along `across`
+    fn parabolic(&mut self, l: f32, along: [f32; 2], across: [f32; 2], d: f32, e: f32) {
+        let f = self.coefficients[5];
+        let x0 = -d / (2.0 * l);
+        // l (X - x0)^2 + E Y + g = 0
+        let g = f - d * d / (4.0 * l);
+        let size = self.coefficients.iter().fold(0.0f32, |m, c| m.max(c.abs()));
+
+        if e.abs() <= TOLERANCE * size {
+            let spread = -g / l;
+            if spread.abs() <= TOLERANCE * (x0 * x0 + f.abs() / l.abs()) {
+                self.class = ConicClass::Line;
+                self.shape = Shape::Lines(vec![Line2{ point: combine([0.0, 0.0], along, x0, across, 0.0), direction: across }]);
+            } else if spread > 0.0 {
+                let half = spread.sqrt();
+                self.class = ConicClass::ParallelLines;
+                self.shape = Shape::Lines([x0 + half, x0 - half].iter()
+                    .map(|&k| Line2{ point: combine([0.0, 0.0], along, k, across, 0.0), direction: across })
+                    .collect());
+            } else {
+                self.class = ConicClass::Empty;
+            }
+            return;
+        }
+
+        // (X - x0)^2 = -(E / l) (Y - y0)
+        let y0 = -g / e;
+        let four_p = -e / l;
+        let opening = if four_p > 0.0 { across } else { [-across[0], -across[1]] };
+        let p = four_p.abs() / 4.0;
+        let vertex = combine([0.0, 0.0], along, x0, across, y0);
+        self.class = ConicClass::Parabola;
+        self.axes = [opening, along];
+        self.vertices = vec![vertex];
+        self.foci = vec![combine(vertex, opening, p, along, 0.0)];
+        self.directrices = vec![Line2{ point: combine(vertex, opening, -p, along, 0.0), direction: along }];
+        self.eccentricity = Some(1.0);
+        self.origin = vertex;
+        self.shape = Shape::Parabola{ p };
+    }
+
+    /// Ax^2 + Bxy + Cy^2 + Dx + Ey + F at (x, y), zero on the conic.
+    pub fn evaluate(&self, x: f32, y: f32) -> f32 {
+        let [a, b, c, d, e, f] = self.coefficients;
+        a * x * x + b * x * y + c * y * y + d * x + e * y + f
+    }
+
+    /// The conic as polylines covering at least the square -reach..reach, one per branch
+    /// or line, each `samples` segments long. A point comes back as a single vertex.
+    pub fn polylines(&self, reach: f32, samples: usize) -> Vec<Vec<[f32; 2]>> {
+        let samples = samples.max(1);
+        let [u, w] = self.axes;
+        let o = self.origin;
+        let steps = |start: f32, end: f32| (0..=samples).map(move |i| start + (end - start) * i as f32 / samples as f32);
+        let distance = (o[0] * o[0] + o[1] * o[1]).sqrt();
+        match &self.shape {
+            Shape::Ellipse{ a, b } => {
+                vec![steps(0.0, 2.0 * PI).map(|t| combine(o, u, a * t.cos(), w, b * t.sin())).collect()]
+            }
+            Shape::Hyperbola{ a, b } => {
+                let s = (2.0 * (reach + distance) / a.min(*b)).asinh();
+                [1.0f32, -1.0].iter().map(|&branch| {
+                    steps(-s, s).map(|t| combine(o, u, branch * a * t.cosh(), w, b * t.sinh())).collect()
+                }).collect()
+            }
+            Shape::Parabola{ p } => {
+                let x_max = (4.0 * p * (2.0 * reach + distance)).sqrt();
+                vec![steps(-x_max, x_max).map(|x| combine(o, u, x * x / (4.0 * p), w, x)).collect()]
+            }
+            Shape::Lines(lines) => lines.iter().map(|line| {
+                let half = 2.0 * reach + (line.point[0].abs() + line.point[1].abs());
+                vec![combine(line.point, line.direction, -half, [0.0, 0.0], 0.0),
+                     combine(line.point, line.direction, half, [0.0, 0.0], 0.0)]
+            }).collect(),
+            Shape::Point => vec![vec![o]],
+            Shape::Empty => Vec::new(),
+        }
+    }
+}
+
+// start + s u + t v
+fn combine(start: [f32; 2], u: [f32; 2], s: f32, v: [f32; 2], t: f32) -> [f32; 2] {
+    [start[0] + s * u[0] + t * v[0], start[1] + s * u[1] + t * v[1]]
+}
+
+fn unit(v: [f32; 2]) -> [f32; 2] {
+    let length = (v[0] * v[0] + v[1] * v[1]).sqrt();
+    if length > 0.0 { [v[0] / length, v[1] / length] } else { [1.0, 0.0] }
+}
+
+// a quarter turn counterclockwise
+fn perpendicular(v: [f32; 2]) -> [f32; 2] {
+    [-v[1], v[0]]
+}
@@ -0,0 +1,190 @@
+/*
+----- The jive curve -----
+Space curves t -> (x, y, z), drawn as connected line segments.
+
+    ----- Units -----
+    like surfaces, the built in curves take their sizes in -10,10 space and come out
+    scaled to -0.5,0.5. A curve made from a closure is used as is
+
+    ----- Sampling -----
+    samples counts intervals across the t range. An open curve gets samples + 1 points,
+    a closed one gets samples points and the polyline joins the last back to the first
+*/
+
+use std::f32::consts::PI;
+use crate::la::linear_algebra::Vec3f;
+use crate::la::linear_algebra::Mat3x3;
+use crate::la::linear_algebra::Mat4x4;
+use crate::la::quaternion::Quat;
+use crate::jives::jivesurface::{positive, SurfaceKind, SCALE};
+
+// how far out an open conic is drawn, in -10,10 units: from the focus for
+// JiveCurve::conic, from the cone's apex for ConicSection::curves
+pub(crate) const CONIC_REACH: f32 = 20.0;
+
+pub struct JiveCurve {
+    function: Box<dyn Fn(f32) -> Vec3f>,
+    t_range: (f32, f32),
+    samples: usize,
+    closed: bool,
+    pub curve_transformation: Mat3x3,
+    /// turned by the rotate_ methods, applied before curve_transformation
+    pub curve_orientation: Quat,
+    /// where the curve sits in the scene, applied after curve_transformation
+    pub curve_placement: Mat4x4,
+}
+
+impl JiveCurve {
+    /// A curve from a closure over `t_range`, split into `samples` segments.
+    pub fn new<F: Fn(f32) -> Vec3f + 'static>(function: F, t_range: (f32, f32), samples: usize) -> Result<JiveCurve, String> {
+        let (t_min, t_max) = t_range;
+        if !(t_min.is_finite() && t_max.is_finite() && t_min < t_max) {
+            return Err(format!("curve range must be finite and increasing, got {t_min}..{t_max}"));
+        }
+        if samples == 0 {
+            return Err("a curve needs at least one segment".to_string());
+        }
+        Ok(JiveCurve{ function: Box::new(function), t_range, samples, closed: false, curve_transformation: Mat3x3::identity(),
+                     curve_orientation: Quat::identity(), curve_placement: Mat4x4::identity() })
+    }
+    /// Joins the end of the curve back to its start.
+    pub fn closed(mut self, closed: bool) -> JiveCurve {
+        self.closed = closed;
+        self
+    }
+
+    /// The segment from `from` to `to`.
+    pub fn line(from: Vec3f, to: Vec3f) -> Result<JiveCurve, String> {
+        finite("line start", &from)?;
+        finite("line end", &to)?;
+        JiveCurve::new(move |t| scaled([
+            from.e[0] + t * (to.e[0] - from.e[0]),
+            from.e[1] + t * (to.e[1] - from.e[1]),
+            from.e[2] + t * (to.e[2] - from.e[2]),
+        ]), (0.0, 1.0), 1)
+    }
+    /// A circle around `center` in the plane through it perpendicular to `normal`.
+    pub fn circle(center: Vec3f, normal: Vec3f, radius: f32) -> Result<JiveCurve, String> {
+        finite("circle center", &center)?;
+        positive("circle radius", radius)?;
+        // the in-plane basis of the plane through the origin with this normal
+        let (_, e1, e2) = SurfaceKind::plane(normal.e[0], normal.e[1], normal.e[2], 0.0)?.plane_basis()?;
+        let curve = JiveCurve::new(move |t| {
+            let (sin, cos) = t.sin_cos();
+            scaled([
+                center.e[0] + radius * (cos * e1.e[0] + sin * e2.e[0]),
+                center.e[1] + radius * (cos * e1.e[1] + sin * e2.e[1]),
+                center.e[2] + radius * (cos * e1.e[2] + sin * e2.e[2]),
+            ])
+        }, (0.0, 2.0 * PI), 90)?;
+        Ok(curve.closed(true))
+    }
+    /// A helix around the z axis starting at (radius, 0, 0), rising `pitch` per turn.
+    pub fn helix(radius: f32, pitch: f32, turns: f32) -> Result<JiveCurve, String> {
+        positive("helix radius", radius)?;
+        positive("helix turns", turns)?;
+        if !pitch.is_finite() {
+            return Err(format!("helix pitch must be finite, got {pitch}"));
+        }
+        let samples = (90.0 * turns).ceil() as usize;
+        JiveCurve::new(move |t| scaled([radius * t.cos(), radius * t.sin(), pitch * t / (2.0 * PI)]),
+                       (0.0, 2.0 * PI * turns), samples)
+    }
+    /// The conic r = p / (1 + e cos t) in the z = 0 plane, focus at the origin and the
+    /// nearest vertex on +x. Ellipses are drawn whole, parabolas and hyperbolas (the
+    /// branch around the focus) are cut off `CONIC_REACH` (20) units from the focus.
+    pub fn conic(eccentricity: f32, semi_latus_rectum: f32) -> Result<JiveCurve, String> {
+        positive("conic semi latus rectum", semi_latus_rectum)?;
+        if !(eccentricity.is_finite() && eccentricity >= 0.0) {
+            return Err(format!("conic eccentricity must be finite and not negative, got {eccentricity}"));
+        }
+        let (e, p) = (eccentricity, semi_latus_rectum);
+        let point = move |t: f32| {
+            let r = p / (1.0 + e * t.cos());
+            scaled([r * t.cos(), r * t.sin(), 0.0])
+        };
+        if e < 1.0 {
+            return Ok(JiveCurve::new(point, (-PI, PI), 180)?.closed(true));
+        }
+        if p / (1.0 + e) >= CONIC_REACH {
+            return Err(format!("conic vertex {} is beyond the drawable reach {CONIC_REACH}", p / (1.0 + e)));
+        }
+        // r <= reach  <=>  cos t >= (p / reach - 1) / e
+        let t_max = ((p / CONIC_REACH - 1.0) / e).clamp(-1.0, 1.0).acos();
+        JiveCurve::new(point, (-t_max, t_max), 180)
+    }
+
+    pub fn t_range(&self) -> (f32, f32) {
+        self.t_range
+    }
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    pub fn set_samples(&mut self, samples: usize) -> Result<(), String> {
+        if samples == 0 {
+            return Err("a curve needs at least one segment".to_string());
+        }
+        self.samples = samples;
+        Ok(())
+    }
+
+    pub fn evaluate(&self, t: f32) -> Vec3f {
+        (self.function)(t)
+    }
+    /// The sampled points, without the closing repeat of the first one.
+    pub fn points(&self) -> Vec<Vec3f> {
+        let (t_min, t_max) = self.t_range;
+        let count = if self.closed { self.samples } else { self.samples + 1 };
+        (0..count).map(|i| self.evaluate(t_min + (t_max - t_min) * i as f32 / self.samples as f32)).collect()
+    }
+    /// The points in drawing order, back to the first one for a closed curve.
+    pub fn polyline(&self) -> Vec<Vec3f> {
+        let mut points = self.points();
+        if self.closed {
+            points.push(points[0]);
+        }
+        points
+    }
+
+    // turned the same way as JiveSurface, so a curve and a surface rotated together stay together
+    pub fn rotate_roll(&mut self, theta: f32) {
+        self.rotate(Quat::rotation_x(theta));
+    }
+    pub fn rotate_pitch(&mut self, theta: f32) {
+        self.rotate(Quat::rotation_y(theta));
+    }
+    pub fn rotate_yaw(&mut self, theta: f32) {
+        self.rotate(Quat::rotation_z(theta));
+    }
+    pub fn rotate_about(&mut self, axis: &Vec3f, theta: f32) -> Result<(), String> {
+        self.rotate(Quat::from_axis_angle(axis, theta)?);
+        Ok(())
+    }
+    fn rotate(&mut self, turn: Quat) {
+        self.curve_orientation = (self.curve_orientation * turn).normalize();
+    }
+    /// The orientation followed by curve_transformation, what the points go through
+    /// before the placement.
+    pub fn transformation(&self) -> Mat3x3 {
+        self.curve_transformation * self.curve_orientation.to_mat3x3()
+    }
+    /// Moves the curve by `offset` in render units.
+    pub fn translate(&mut self, offset: Vec3f) {
+        self.curve_placement *= &Mat4x4::translation(&offset);
+    }
+}
+
+fn scaled(p: [f32; 3]) -> Vec3f {
+    Vec3f::from(p[0] * SCALE, p[1] * SCALE, p[2] * SCALE)
+}
+
+fn finite(name: &str, p: &Vec3f) -> Result<(), String> {
+    if p.e.iter().all(|c| c.is_finite()) {
+        Ok(())
+    } else {
+        Err(format!("{name} must be finite, got {:?}", p.e))
+    }
+}
@@ -0,0 +1,171 @@
+/*
+----- The jive dandelin -----
+Dandelin spheres: the spheres inside the double cone z^2 = steepness^2 (x^2 + y^2) that
+touch both the cone and a cutting plane. They touch the plane at the foci of the section
+and the cone along circles, which is the classic proof of the focus/directrix property.
+
+    ----- Finding them -----
+    a sphere inside the cone sits on the axis at (0, 0, z0) with radius |z0| sin(alpha),
+    alpha the half angle of the cone (tan alpha = 1 / steepness). Touching the plane
+    n . p = d (n a unit vector) means n_z z0 - d = +-|z0| sin(alpha), which gives up to two
+    spheres: two on one nappe for an ellipse, one on each for a hyperbola, one for a parabola
+
+    ----- The circles -----
+    the sphere touches every line through the apex at the foot of the perpendicular from
+    its center, which is the circle at height z0 cos^2(alpha) of radius |z0| sin cos
+
+    everything here is in -10,10 units, the surfaces and curves are scaled as usual
+*/
+
+use crate::la::linear_algebra::Vec3f;
+use crate::la::linear_algebra::Mat3x3;
+use crate::jives::jivecurve::JiveCurve;
+use crate::jives::jiveparametric::ParametricSurface;
+use crate::jives::jivesection::ConicSection;
+use crate::jives::jivesurface::{JiveSurface, SurfaceKind, SCALE};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DandelinSphere {
+    pub center: Vec3f,
+    pub radius: f32,
+    /// where the sphere touches the plane, a focus of the section
+    pub focus: Vec3f,
+    /// the circle where the sphere touches the cone lies in the plane z = tangency_height
+    pub tangency_height: f32,
+    pub tangency_radius: f32,
+}
+
+/// The cone, the plane, the section and its Dandelin spheres, ready to draw.
+pub struct DandelinScene {
+    pub section: ConicSection,
+    pub spheres: Vec<DandelinSphere>,
+    /// both nappes of the cone
+    pub cone: JiveSurface,
+    pub plane: JiveSurface,
+    pub sphere_surfaces: Vec<JiveSurface>,
+    pub section_curves: Vec<JiveCurve>,
+    pub tangency_circles: Vec<JiveCurve>,
+    /// applied to the foci, rotate_roll keeps it in step with the surfaces and curves
+    pub scene_transformation: Mat3x3,
+}
+
+impl DandelinScene {
+    /// Takes a `SurfaceKind::Cone` (the double cone it is the top half of is used) and a
+    /// `SurfaceKind::Plane`. A plane through the apex has no spheres.
+    pub fn new(cone: &SurfaceKind, plane: &SurfaceKind) -> Result<DandelinScene, String> {
+        let section = ConicSection::new(cone, plane)?;
+        let (steepness, height) = match *cone {
+            SurfaceKind::Cone { steepness, height } => (steepness, height),
+            _ => return Err(format!("{cone:?} is not a cone")),
+        };
+        let spheres = dandelin_spheres(steepness, plane)?;
+
+        let cone_surface = JiveSurface::new(SurfaceKind::elliptic_cone(1.0 / steepness, 1.0 / steepness, height)?)?;
+        let sphere_surfaces = spheres.iter().map(|sphere| {
+            let kind = SurfaceKind::sphere(sphere.radius)?;
+            Ok(JiveSurface::from_parametric(Offset{ kind, offset: sphere.center }, 36, 18))
+        }).collect::<Result<Vec<JiveSurface>, String>>()?;
+        let tangency_circles = spheres.iter().map(|sphere| {
+            JiveCurve::circle(Vec3f::from(0.0, 0.0, sphere.tangency_height), Vec3f::from(0.0, 0.0, 1.0), sphere.tangency_radius)
+        }).collect::<Result<Vec<JiveCurve>, String>>()?;
+
+        Ok(DandelinScene{ section, spheres, cone: cone_surface, plane: JiveSurface::new(*plane)?, sphere_surfaces,
+                          section_curves: section.curves()?, tangency_circles, scene_transformation: Mat3x3::identity() })
+    }
+
+    /// The foci scaled like the surfaces, before any transformation.
+    pub fn foci(&self) -> Vec<Vec3f> {
+        self.spheres.iter().map(|sphere| {
+            let f = sphere.focus.e;
+            Vec3f::from(f[0] * SCALE, f[1] * SCALE, f[2] * SCALE)
+        }).collect()
+    }
+
+    pub fn rotate_roll(&mut self, theta: f32) {
+        self.cone.rotate_roll(theta);
+        self.plane.rotate_roll(theta);
+        for surface in self.sphere_surfaces.iter_mut() {
+            surface.rotate_roll(theta);
+        }
+        for curve in self.section_curves.iter_mut().chain(self.tangency_circles.iter_mut()) {
+            curve.rotate_roll(theta);
+        }
+        let roll_mat = Mat3x3::roll(theta);
+        self.scene_transformation *= &roll_mat;
+    }
+}
+
+/// The spheres inscribed in the double cone z^2 = steepness^2 (x^2 + y^2) and tangent
+/// to `plane`, nearest the apex first.
+pub fn dandelin_spheres(steepness: f32, plane: &SurfaceKind) -> Result<Vec<DandelinSphere>, String> {
+    if !(steepness.is_finite() && steepness > 0.0) {
+        return Err(format!("cone steepness must be positive and finite, got {steepness}"));
+    }
+    plane.validate()?;
+    let (a, b, c, d) = match *plane {
+        SurfaceKind::Plane { a, b, c, d, .. } => (a, b, c, d),
+        _ => return Err(format!("{plane:?} is not a plane")),
+    };
+    let length = (a * a + b * b + c * c).sqrt();
+    let (n, d) = ([a / length, b / length, c / length], d / length);
+    let sin = 1.0 / (1.0 + steepness * steepness).sqrt();
+    let cos = steepness * sin;
+
+    let mut spheres: Vec<DandelinSphere> = Vec::new();
+    if d == 0.0 {
+        return Ok(spheres);
+    }
+    // nappe is the sign of z0, side picks which way the plane is touched
+    for nappe in [1.0f32, -1.0] {
+        for side in [1.0f32, -1.0] {
+            let denominator = n[2] - side * nappe * sin;
+            if denominator.abs() <= f32::EPSILON {
+                continue;
+            }
+            let z0 = d / denominator;
+            if z0.signum() != nappe || spheres.iter().any(|s| (s.center.e[2] - z0).abs() <= 1e-5 * z0.abs()) {
+                continue;
+            }
+            let radius = z0.abs() * sin;
+            // the foot of the perpendicular from the center to the plane
+            let above = n[2] * z0 - d;
+            let focus = Vec3f::from(-above * n[0], -above * n[1], z0 - above * n[2]);
+            spheres.push(DandelinSphere{ center: Vec3f::from(0.0, 0.0, z0), radius, focus,
+                                         tangency_height: z0 * cos * cos, tangency_radius: radius * cos });
+        }
+    }
+    spheres.sort_by(|p, q| p.center.e[2].abs().total_cmp(&q.center.e[2].abs()));
+    Ok(spheres)
+}
+
+// a built in surface moved off the origin, offset in -10,10 units
+struct Offset {
+    kind: SurfaceKind,
+    offset: Vec3f,
+}
+
+impl ParametricSurface for Offset {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        let p = self.kind.evaluate(u, v);
+        let o = self.offset.e;
+        Vec3f::from(p.e[0] + o[0] * SCALE, p.e[1] + o[1] * SCALE, p.e[2] + o[2] * SCALE)
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        self.kind.u_domain()
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        self.kind.v_domain()
+    }
+    fn u_periodic(&self) -> bool {
+        self.kind.u_periodic()
+    }
+    fn v_periodic(&self) -> bool {
+        self.kind.v_periodic()
+    }
+    fn partials(&self, u: f32, v: f32) -> (Vec3f, Vec3f) {
+        self.kind.partials(u, v)
+    }
+    fn normal(&self, u: f32, v: f32) -> Vec3f {
+        self.kind.normal(u, v)
+    }
+}
@@ -0,0 +1,184 @@
+/*
+----- Implicit surfaces -----
+Surfaces given as f(x, y, z) = 0 with no handy parametrization, like
+x^4 + y^4 + z^4 - xyz = 1, polygonized into the same Mesh the parametric surfaces use.
+
+    ----- Marching tetrahedra -----
+    the bounding box is cut into cells, every cell into six tetrahedra around its
+    main diagonal (neighbouring cells then agree on how their shared faces are split)
+    and every tetrahedron the surface passes through gets one or two triangles.
+    Vertices on shared grid edges are shared, so the mesh comes out indexed and
+    without cracks. Normals are the gradient of f, pointing toward f > 0
+
+    ----- Units -----
+    f and the bounds are in the same -10,10 space as the built in shapes and the
+    quadrics, and the mesh comes out scaled by SCALE like they do, so x^2 + y^2 + z^2 = 25
+    lands on top of Quadric::to_surface of the same equation
+*/
+
+use std::collections::HashMap;
+
+use crate::la::linear_algebra::Vec3f;
+use crate::jives::jivemesh::Mesh;
+use crate::jives::jivesurface::{JiveSurface, SCALE};
+
+// cells per axis is capped so a typo can't ask for a billion samples
+const MAX_RESOLUTION: usize = 512;
+
+// cube corners as (x, y, z) offsets
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0], [1, 0, 0], [1, 1, 0], [0, 1, 0],
+    [0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1],
+];
+// six tetrahedra sharing the 0-6 diagonal
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 5, 1, 6], [0, 1, 2, 6], [0, 2, 3, 6],
+    [0, 3, 7, 6], [0, 7, 4, 6], [0, 4, 5, 6],
+];
+
+/// Axis aligned box the implicit function is sampled in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Bounds {
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Result<Bounds, String> {
+        for axis in 0..3 {
+            if !(min[axis].is_finite() && max[axis].is_finite()) || min[axis] >= max[axis] {
+                return Err(format!("bounds need finite min < max on every axis, got {:?} to {:?}", min, max));
+            }
+        }
+        Ok(Bounds{ min, max })
+    }
+    /// The cube from -half_size to half_size on every axis.
+    pub fn cube(half_size: f32) -> Result<Bounds, String> {
+        Bounds::new([-half_size; 3], [half_size; 3])
+    }
+}
+
+/// Triangulates f(x, y, z) = 0 inside `bounds` on a grid of `resolution` cells per axis,
+/// positions scaled by SCALE into render units. uvs are (0, 0), an implicit surface has
+/// no parameters.
+pub fn polygonize<F: Fn(f32, f32, f32) -> f32>(f: F, bounds: &Bounds, resolution: usize) -> Result<Mesh, String> {
+    if resolution == 0 || resolution > MAX_RESOLUTION {
+        return Err(format!("resolution must be between 1 and {MAX_RESOLUTION}, got {resolution}"));
+    }
+    let n = resolution;
+    let step = [(bounds.max[0] - bounds.min[0]) / n as f32,
+                (bounds.max[1] - bounds.min[1]) / n as f32,
+                (bounds.max[2] - bounds.min[2]) / n as f32];
+    let position = |i: usize, j: usize, k: usize| Vec3f::from(bounds.min[0] + i as f32 * step[0],
+                                                              bounds.min[1] + j as f32 * step[1],
+                                                              bounds.min[2] + k as f32 * step[2]);
+    let grid_index = |i: usize, j: usize, k: usize| (k * (n + 1) + j) * (n + 1) + i;
+
+    // sample f once at every grid point
+    let mut values = vec![0.0f32; (n + 1) * (n + 1) * (n + 1)];
+    for k in 0..=n {
+        for j in 0..=n {
+            for i in 0..=n {
+                let [x, y, z] = position(i, j, k).e;
+                values[grid_index(i, j, k)] = f(x, y, z);
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new();
+    // grid edge (lower index, higher index) -> mesh vertex on it
+    let mut edge_vertices: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for k in 0..n {
+        for j in 0..n {
+            for i in 0..n {
+                let corner_index: Vec<usize> = CORNERS.iter().map(|c| grid_index(i + c[0], j + c[1], k + c[2])).collect();
+                let corner_position: Vec<Vec3f> = CORNERS.iter().map(|c| position(i + c[0], j + c[1], k + c[2])).collect();
+
+                for tet in TETRAHEDRA.iter() {
+                    let ids = tet.map(|c| corner_index[c]);
+                    let points = tet.map(|c| corner_position[c]);
+                    let fs = ids.map(|id| values[id]);
+                    if fs.iter().any(|v| !v.is_finite()) {
+                        continue;
+                    }
+                    let inside: Vec<usize> = (0..4).filter(|&t| fs[t] < 0.0).collect();
+                    let outside: Vec<usize> = (0..4).filter(|&t| fs[t] >= 0.0).collect();
+
+                    let mut vertex = |a: usize, b: usize| -> usize {
+                        let key = (ids[a].min(ids[b]), ids[a].max(ids[b]));
+                        *edge_vertices.entry(key).or_insert_with(|| {
+                            let t = fs[a] / (fs[a] - fs[b]);
+                            mesh.positions.push(points[a].lerp(&points[b], t));
+                            mesh.positions.len() - 1
+                        })
+                    };
+
+                    let triangles: Vec<[usize; 3]> = match (inside.len(), outside.len()) {
+                        (1, 3) => {
+                            let a = inside[0];
+                            vec![[vertex(a, outside[0]), vertex(a, outside[1]), vertex(a, outside[2])]]
+                        }
+                        (3, 1) => {
+                            let a = outside[0];
+                            vec![[vertex(inside[0], a), vertex(inside[1], a), vertex(inside[2], a)]]
+                        }
+                        (2, 2) => {
+                            // the four crossing edges form a quad, walked around in order
+                            let (a, b) = (inside[0], inside[1]);
+                            let (c, d) = (outside[0], outside[1]);
+                            let quad = [vertex(a, c), vertex(a, d), vertex(b, d), vertex(b, c)];
+                            vec![[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]]
+                        }
+                        _ => Vec::new(),
+                    };
+
+                    // wind every triangle so its normal points from inside to outside
+                    let toward_outside = centroid(&outside, &points) - centroid(&inside, &points);
+                    for tri in triangles {
+                        if tri[0] == tri[1] || tri[1] == tri[2] || tri[0] == tri[2] {
+                            continue;
+                        }
+                        let p0 = mesh.positions[tri[0]];
+                        let face = (mesh.positions[tri[1]] - p0).cross(&(mesh.positions[tri[2]] - p0));
+                        if face.dot(&toward_outside) < 0.0 {
+                            mesh.indices.push([tri[0], tri[2], tri[1]]);
+                        } else {
+                            mesh.indices.push(tri);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // gradient normals by central differences, a fraction of a cell wide
+    let h = [step[0] * 0.25, step[1] * 0.25, step[2] * 0.25];
+    mesh.normals = mesh.positions.iter().map(|p| {
+        let [x, y, z] = p.e;
+        let gradient = Vec3f::from((f(x + h[0], y, z) - f(x - h[0], y, z)) / (2.0 * h[0]),
+                                   (f(x, y + h[1], z) - f(x, y - h[1], z)) / (2.0 * h[1]),
+                                   (f(x, y, z + h[2]) - f(x, y, z - h[2])) / (2.0 * h[2]));
+        let length = gradient.magnitude();
+        if length > 0.0 && length.is_finite() { gradient.normalize() } else { Vec3f::from(0.0, 0.0, 0.0) }
+    }).collect();
+    mesh.uvs = vec![[0.0, 0.0]; mesh.positions.len()];
+    // the normals were taken where f lives, scaling every axis the same leaves them as they are
+    for p in mesh.positions.iter_mut() {
+        *p *= SCALE;
+    }
+
+    Ok(mesh)
+}
+
+impl JiveSurface {
+    /// f(x, y, z) = 0 inside `bounds`, polygonized with `resolution` cells per axis.
+    pub fn implicit<F: Fn(f32, f32, f32) -> f32>(f: F, bounds: &Bounds, resolution: usize) -> Result<JiveSurface, String> {
+        Ok(JiveSurface::from_mesh(polygonize(f, bounds, resolution)?))
+    }
+}
+
+fn centroid(which: &[usize], points: &[Vec3f; 4]) -> Vec3f {
+    let sum = which.iter().fold(Vec3f::zero(), |sum, &t| sum + points[t]);
+    sum / which.len().max(1) as f32
+}
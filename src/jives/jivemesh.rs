@@ -0,0 +1,219 @@
+/*
+----- The jive mesh -----
+Indexed triangles built from the u/v grid a surface is sampled on.
+
+    ----- Layout -----
+    positions, normals and uvs are per vertex, indices are per triangle
+    uvs are the (u, v) parameters scaled to 0,1 over the sampled domain
+
+    ----- Seams and poles -----
+    when u wraps (u = 360 degrees is u = 0) the last column is stitched back to the
+    first one instead of being duplicated (same for v and the rows), and a row that
+    collapses to a single point (the poles of a sphere, the apex of a double cone in
+    the middle of the grid) becomes one vertex and a triangle fan on either side
+
+    ----- Normals -----
+    vertex normals come from the surface itself when it was sampled with them, and
+    triangles are wound to agree with those. A pole gets the average of its row's
+    normals, anything the surface could not give a normal falls back to the face normals
+*/
+
+use crate::la::linear_algebra::Vec3f;
+use crate::jives::jiveparametric::{ParametricSurface, SampleGrid, Sampling, sample_grid, collapses, extent};
+
+#[derive(Clone)]
+pub struct Mesh {
+    pub positions: Vec<Vec3f>,
+    pub normals: Vec<Vec3f>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<[usize; 3]>,
+}
+
+/// How the rows and columns of a sampled grid join up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridTopology {
+    /// the last column connects back to the first
+    pub u_wraps: bool,
+    /// the last row connects back to the first
+    pub v_wraps: bool,
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        Mesh{ positions: Vec::new(), normals: Vec::new(), uvs: Vec::new(), indices: Vec::new() }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Samples a parametric surface and triangulates it, see `from_grid`.
+    pub fn from_parametric(surface: &dyn ParametricSurface, sampling: &Sampling) -> Result<Mesh, String> {
+        sampling.validate()?;
+        let grid = sample_grid(surface, sampling);
+        let topology = GridTopology{ u_wraps: sampling.u_wraps(surface), v_wraps: sampling.v_wraps(surface) };
+        Mesh::from_grid(&grid, topology)
+    }
+
+    /// Every sample of a grid as a vertex and no triangles, for a grid too thin
+    /// for `from_grid` (a single row or column).
+    pub fn from_samples(grid: &SampleGrid) -> Mesh {
+        let mut mesh = Mesh::new();
+        for (i, row) in grid.points.iter().enumerate() {
+            for (j, p) in row.iter().enumerate() {
+                mesh.positions.push(*p);
+                mesh.normals.push(grid.normals.get(i).and_then(|normals| normals.get(j)).copied().unwrap_or_default());
+                mesh.uvs.push([grid.u_params[j], grid.v_params[i]]);
+            }
+        }
+        mesh
+    }
+
+    /// Triangulates a sampled grid. Every row must have the same number of samples.
+    /// A row whose samples all land on the same point is treated as a pole.
+    /// Leave `grid.normals` empty to get face normals and du x dv winding.
+    pub fn from_grid(grid: &SampleGrid, topology: GridTopology) -> Result<Mesh, String> {
+        let (u_params, v_params) = (&grid.u_params, &grid.v_params);
+        let normals = &grid.normals;
+        let grid = &grid.points;
+        let rows = grid.len();
+        let cols = u_params.len();
+        if rows < 2 || cols < 2 {
+            return Err(format!("a mesh needs at least a 2x2 grid, got {rows}x{cols}"));
+        }
+        if v_params.len() != rows || grid.iter().any(|row| row.len() != cols) {
+            return Err("grid rows do not match the u/v parameters".to_string());
+        }
+        let has_normals = !normals.is_empty();
+        if has_normals && (normals.len() != rows || normals.iter().any(|row| row.len() != cols)) {
+            return Err("grid normals do not match the grid points".to_string());
+        }
+        let zero = Vec3f::from(0.0, 0.0, 0.0);
+        let tolerance = 1e-5 * extent(grid).max(f32::MIN_POSITIVE);
+        let is_pole: Vec<bool> = grid.iter().map(|row| collapses(row, tolerance)).collect();
+
+        let mut mesh = Mesh::new();
+        // the surface's own normal at each vertex, zero where it has none
+        let mut sampled: Vec<Vec3f> = Vec::new();
+
+        // vertex index of every grid sample, collapsed rows share one vertex
+        let mut index: Vec<Vec<usize>> = Vec::with_capacity(rows);
+        for (i, row) in grid.iter().enumerate() {
+            if is_pole[i] {
+                let pole = mesh.positions.len();
+                mesh.positions.push(average(row));
+                mesh.uvs.push([0.5, v_params[i]]);
+                sampled.push(if has_normals { unit_or_zero(sum(&normals[i])) } else { zero });
+                index.push(vec![pole; cols]);
+            } else {
+                let mut row_index = Vec::with_capacity(cols);
+                for (j, p) in row.iter().enumerate() {
+                    row_index.push(mesh.positions.len());
+                    mesh.positions.push(*p);
+                    mesh.uvs.push([u_params[j], v_params[i]]);
+                    sampled.push(if has_normals { unit_or_zero(normals[i][j]) } else { zero });
+                }
+                index.push(row_index);
+            }
+        }
+
+        // two triangles per quad, wound so the face normal follows du x dv
+        let quads_across = if topology.u_wraps { cols } else { cols - 1 };
+        let quads_down = if topology.v_wraps { rows } else { rows - 1 };
+        for i in 0..quads_down {
+            let i_next = (i + 1) % rows;
+            for j in 0..quads_across {
+                let j_next = (j + 1) % cols;
+                let a = index[i][j];
+                let b = index[i][j_next];
+                let c = index[i_next][j];
+                let d = index[i_next][j_next];
+                // a fan around a pole drops the triangle that folds onto it
+                if a != b {
+                    mesh.indices.push([a, b, d]);
+                }
+                if c != d {
+                    mesh.indices.push([a, d, c]);
+                }
+            }
+        }
+
+        // turn round any triangle facing away from the surface's normals
+        for tri in mesh.indices.iter_mut() {
+            let hint = sum(&[sampled[tri[0]], sampled[tri[1]], sampled[tri[2]]]);
+            if face_normal(&mesh.positions, tri).dot(&hint) < 0.0 {
+                tri.swap(1, 2);
+            }
+        }
+
+        mesh.compute_normals();
+        for (normal, own) in mesh.normals.iter_mut().zip(sampled.iter()) {
+            if own.magnitude() > 0.0 {
+                *normal = *own;
+            }
+        }
+        Ok(mesh)
+    }
+
+    /// Every edge once, as (smaller index, larger index).
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self.indices.iter()
+            .flat_map(|tri| [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])])
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Adds another mesh's vertices and triangles to this one.
+    pub fn append(&mut self, other: Mesh) {
+        let offset = self.positions.len();
+        self.positions.extend(other.positions);
+        self.normals.extend(other.normals);
+        self.uvs.extend(other.uvs);
+        self.indices.extend(other.indices.iter().map(|tri| [tri[0] + offset, tri[1] + offset, tri[2] + offset]));
+    }
+
+    /// Area weighted average of the face normals around each vertex.
+    pub fn compute_normals(&mut self) {
+        let mut sums = vec![[0.0f32; 3]; self.positions.len()];
+        for tri in self.indices.iter() {
+            // the cross product's length is twice the area, which is the weighting we want
+            let face = face_normal(&self.positions, tri);
+            for &k in tri.iter() {
+                for (sum, f) in sums[k].iter_mut().zip(face.e.iter()) {
+                    *sum += f;
+                }
+            }
+        }
+        self.normals = sums.iter().map(|s| unit_or_zero(Vec3f::new(*s))).collect();
+    }
+}
+
+impl Default for Mesh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// unnormalized, twice the triangle's area long
+fn face_normal(positions: &[Vec3f], tri: &[usize; 3]) -> Vec3f {
+    let p0 = positions[tri[0]];
+    (positions[tri[1]] - p0).cross(&(positions[tri[2]] - p0))
+}
+
+fn unit_or_zero(n: Vec3f) -> Vec3f {
+    n.try_normalize().unwrap_or_default()
+}
+
+fn sum(points: &[Vec3f]) -> Vec3f {
+    points.iter().fold(Vec3f::zero(), |sum, p| sum + p)
+}
+
+fn average(points: &[Vec3f]) -> Vec3f {
+    sum(points) / points.len().max(1) as f32
+}
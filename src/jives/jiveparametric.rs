@@ -0,0 +1,303 @@
+/*
+----- Parametric surfaces -----
+Anything that maps (u, v) to a point can be handed to jive. The built in shapes
+(SurfaceKind) implement this the same way a user defined surface would, so
+JiveSurface, the mesh generator and the renderers never need to know which is which.
+
+    ----- Normals -----
+    the normal is du x dv, normalized. Surfaces that know their partial
+    derivatives should say so, everything else gets central differences. Where the
+    cross product vanishes (the pole of a sphere) the normal is taken a little way
+    into the domain instead
+
+    ----- Sampling -----
+    a Sampling picks the u/v ranges to cover (any part of the surface's domain)
+    and how many intervals to split each into, so a non periodic direction gets
+    samples + 1 points and a periodic one gets exactly `samples` points
+    (the end of the domain is the start again)
+*/
+
+use crate::la::linear_algebra::Vec3f;
+
+pub trait ParametricSurface {
+    /// Point on the surface at parameters (u, v).
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f;
+    /// (min, max) of the u parameter.
+    fn u_domain(&self) -> (f32, f32);
+    /// (min, max) of the v parameter.
+    fn v_domain(&self) -> (f32, f32);
+    /// evaluate(u_max, v) is the same point as evaluate(u_min, v)
+    fn u_periodic(&self) -> bool {
+        false
+    }
+    /// evaluate(u, v_max) is the same point as evaluate(u, v_min)
+    fn v_periodic(&self) -> bool {
+        false
+    }
+    /// (dP/du, dP/dv) at (u, v), central differences unless the surface knows better.
+    fn partials(&self, u: f32, v: f32) -> (Vec3f, Vec3f) {
+        finite_difference_partials(self, u, v)
+    }
+    /// Unit normal at (u, v), the zero vector if the surface is degenerate all around it.
+    fn normal(&self, u: f32, v: f32) -> Vec3f {
+        normal_from_partials(self, u, v)
+    }
+}
+
+// parameter step for finite differences and for stepping off a degenerate point
+const PARAMETER_EPSILON: f32 = 1.0e-3;
+
+/// Central differences a small fraction of the domain wide, kept inside the domain.
+pub fn finite_difference_partials<S: ParametricSurface + ?Sized>(surface: &S, u: f32, v: f32) -> (Vec3f, Vec3f) {
+    let difference = |(min, max): (f32, f32), t: f32, at: &dyn Fn(f32) -> Vec3f| {
+        let h = PARAMETER_EPSILON * (max - min);
+        let (lo, hi) = ((t - h).max(min), (t + h).min(max));
+        let (a, b) = (at(lo), at(hi));
+        let span = hi - lo;
+        Vec3f::from((b.e[0] - a.e[0]) / span, (b.e[1] - a.e[1]) / span, (b.e[2] - a.e[2]) / span)
+    };
+    let du = difference(surface.u_domain(), u, &|t| surface.evaluate(t, v));
+    let dv = difference(surface.v_domain(), v, &|t| surface.evaluate(u, t));
+    (du, dv)
+}
+
+/// du x dv normalized, stepping a little way into the domain when it vanishes.
+pub fn normal_from_partials<S: ParametricSurface + ?Sized>(surface: &S, u: f32, v: f32) -> Vec3f {
+    let (umin, umax) = surface.u_domain();
+    let (vmin, vmax) = surface.v_domain();
+    let inward = |t: f32, min: f32, max: f32| {
+        let step = PARAMETER_EPSILON * (max - min);
+        if t - min < max - t { t + step } else { t - step }
+    };
+    let attempts = [(u, v), (u, inward(v, vmin, vmax)), (inward(u, umin, umax), v)];
+    for (u, v) in attempts {
+        let (du, dv) = surface.partials(u, v);
+        let n = du.cross(&dv);
+        let length = n.magnitude();
+        // against |du|^2 + |dv|^2 so that one partial all but vanishing counts as degenerate
+        let (a, b) = (du.magnitude(), dv.magnitude());
+        if length > 1.0e-5 * (a * a + b * b) && length.is_finite() {
+            return n.normalize();
+        }
+    }
+    Vec3f::from(0.0, 0.0, 0.0)
+}
+
+/// A parametric surface built from a closure, for one off shapes that
+/// don't deserve their own type.
+pub struct FnSurface<F: Fn(f32, f32) -> Vec3f> {
+    function: F,
+    u_domain: (f32, f32),
+    v_domain: (f32, f32),
+    u_periodic: bool,
+    v_periodic: bool,
+}
+
+impl<F: Fn(f32, f32) -> Vec3f> FnSurface<F> {
+    pub fn new(function: F, u_domain: (f32, f32), v_domain: (f32, f32)) -> FnSurface<F> {
+        FnSurface{ function, u_domain, v_domain, u_periodic: false, v_periodic: false }
+    }
+    pub fn periodic(mut self, u_periodic: bool, v_periodic: bool) -> FnSurface<F> {
+        self.u_periodic = u_periodic;
+        self.v_periodic = v_periodic;
+        self
+    }
+}
+
+impl<F: Fn(f32, f32) -> Vec3f> ParametricSurface for FnSurface<F> {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        (self.function)(u, v)
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        self.u_domain
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        self.v_domain
+    }
+    fn u_periodic(&self) -> bool {
+        self.u_periodic
+    }
+    fn v_periodic(&self) -> bool {
+        self.v_periodic
+    }
+}
+
+/// Which part of a surface's (u, v) domain gets sampled and how finely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    pub u_range: (f32, f32),
+    pub v_range: (f32, f32),
+    /// intervals across u_range
+    pub u_samples: usize,
+    /// intervals across v_range
+    pub v_samples: usize,
+}
+
+impl Sampling {
+    /// The surface's whole domain split into the given number of intervals.
+    pub fn new(surface: &dyn ParametricSurface, u_samples: usize, v_samples: usize) -> Sampling {
+        Sampling{ u_range: surface.u_domain(), v_range: surface.v_domain(), u_samples, v_samples }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, (min, max)) in [("u", self.u_range), ("v", self.v_range)] {
+            if !(min.is_finite() && max.is_finite()) || min >= max {
+                return Err(format!("{name} range must be finite with min < max, got ({min}, {max})"));
+            }
+        }
+        if self.u_samples == 0 || self.v_samples == 0 {
+            return Err(format!("sample counts must be at least 1, got {} x {}", self.u_samples, self.v_samples));
+        }
+        Ok(())
+    }
+
+    /// u only wraps around when the whole periodic domain is being sampled.
+    pub fn u_wraps(&self, surface: &dyn ParametricSurface) -> bool {
+        surface.u_periodic() && self.u_range == surface.u_domain()
+    }
+    pub fn v_wraps(&self, surface: &dyn ParametricSurface) -> bool {
+        surface.v_periodic() && self.v_range == surface.v_domain()
+    }
+
+    /// Picks sample counts so neighbouring samples land roughly `edge_length` apart,
+    /// measured along a handful of parameter lines across the ranges.
+    pub fn with_edge_length(mut self, surface: &dyn ParametricSurface, edge_length: f32) -> Result<Sampling, String> {
+        if !(edge_length.is_finite() && edge_length > 0.0) {
+            return Err(format!("edge length must be positive and finite, got {edge_length}"));
+        }
+        self.validate()?;
+        let (umin, umax) = self.u_range;
+        let (vmin, vmax) = self.v_range;
+        let mut u_length: f32 = 0.0;
+        let mut v_length: f32 = 0.0;
+        for i in 0..=PROBE_LINES {
+            let t = i as f32 / PROBE_LINES as f32;
+            let u = umin + (umax - umin) * t;
+            let v = vmin + (vmax - vmin) * t;
+            u_length = u_length.max(polyline_length(|s| surface.evaluate(umin + (umax - umin) * s, v)));
+            v_length = v_length.max(polyline_length(|s| surface.evaluate(u, vmin + (vmax - vmin) * s)));
+        }
+        self.u_samples = ((u_length / edge_length).ceil() as usize).clamp(1, MAX_SAMPLES);
+        self.v_samples = ((v_length / edge_length).ceil() as usize).clamp(1, MAX_SAMPLES);
+        Ok(self)
+    }
+}
+
+// parameter lines measured in each direction by with_edge_length
+const PROBE_LINES: usize = 4;
+// segments per measured line
+const PROBE_SEGMENTS: usize = 64;
+const MAX_SAMPLES: usize = 4096;
+
+fn polyline_length<F: Fn(f32) -> Vec3f>(point_at: F) -> f32 {
+    let mut length = 0.0;
+    let mut previous = point_at(0.0);
+    for k in 1..=PROBE_SEGMENTS {
+        let p = point_at(k as f32 / PROBE_SEGMENTS as f32);
+        let (dx, dy, dz) = (p.e[0] - previous.e[0], p.e[1] - previous.e[1], p.e[2] - previous.e[2]);
+        let step = (dx * dx + dy * dy + dz * dz).sqrt();
+        if step.is_finite() {
+            length += step;
+        }
+        previous = p;
+    }
+    length
+}
+
+/// Samples of a surface laid out as `points[v_index][u_index]`, the normals there
+/// (empty when only the points were sampled), and the parameters that produced them
+/// scaled to 0,1 over the sampled ranges.
+pub struct SampleGrid {
+    pub points: Vec<Vec<Vec3f>>,
+    pub normals: Vec<Vec<Vec3f>>,
+    pub u_params: Vec<f32>,
+    pub v_params: Vec<f32>,
+}
+
+/// The points and the normals there.
+pub fn sample_grid(surface: &dyn ParametricSurface, sampling: &Sampling) -> SampleGrid {
+    let mut grid = sample_points(surface, sampling);
+    let (umin, umax) = sampling.u_range;
+    let (vmin, vmax) = sampling.v_range;
+    grid.normals = grid.v_params.iter().map(|&t_v| {
+        grid.u_params.iter().map(|&t_u| {
+            surface.normal(umin + (umax - umin) * t_u, vmin + (vmax - vmin) * t_v)
+        }).collect()
+    }).collect();
+    grid
+}
+
+/// Just the points, for when the normals (and the partials behind them) aren't wanted.
+pub fn sample_points(surface: &dyn ParametricSurface, sampling: &Sampling) -> SampleGrid {
+    let u_params = parameter_steps(sampling.u_samples, sampling.u_wraps(surface));
+    let v_params = parameter_steps(sampling.v_samples, sampling.v_wraps(surface));
+    let (umin, umax) = sampling.u_range;
+    let (vmin, vmax) = sampling.v_range;
+
+    let points = v_params.iter().map(|&t_v| {
+        u_params.iter().map(|&t_u| {
+            surface.evaluate(umin + (umax - umin) * t_u, vmin + (vmax - vmin) * t_v)
+        }).collect()
+    }).collect();
+
+    SampleGrid{ points, normals: Vec::new(), u_params, v_params }
+}
+
+/// Polylines along `u_lines` constant-u and `v_lines` constant-v parameter lines,
+/// spread evenly over the sampled ranges. How many lines there are is independent
+/// of how finely each line is sampled, which follows the sampling's counts. A
+/// constant-v line that collapses to a point (the poles of a sphere) is left out.
+pub fn isolines(surface: &dyn ParametricSurface, sampling: &Sampling, u_lines: usize, v_lines: usize) -> Vec<Vec<Vec3f>> {
+    let (umin, umax) = sampling.u_range;
+    let (vmin, vmax) = sampling.v_range;
+    let u_samples = sampling.u_samples.max(1);
+    let v_samples = sampling.v_samples.max(1);
+
+    let mut lines = Vec::with_capacity(u_lines + v_lines);
+    // a wrapping direction would draw the last line on top of the first
+    let u_divisions = if sampling.u_wraps(surface) { u_lines } else { u_lines.saturating_sub(1) };
+    let v_divisions = if sampling.v_wraps(surface) { v_lines } else { v_lines.saturating_sub(1) };
+
+    for i in 0..u_lines {
+        let u = umin + (umax - umin) * i as f32 / u_divisions.max(1) as f32;
+        // sampling up to the end of the range closes the loop when v wraps
+        lines.push((0..=v_samples).map(|k| {
+            surface.evaluate(u, vmin + (vmax - vmin) * k as f32 / v_samples as f32)
+        }).collect());
+    }
+    let mut v_isolines: Vec<Vec<Vec3f>> = Vec::with_capacity(v_lines);
+    for i in 0..v_lines {
+        let v = vmin + (vmax - vmin) * i as f32 / v_divisions.max(1) as f32;
+        v_isolines.push((0..=u_samples).map(|k| {
+            surface.evaluate(umin + (umax - umin) * k as f32 / u_samples as f32, v)
+        }).collect());
+    }
+    // same tolerance the mesh uses for its poles
+    let tolerance = 1e-5 * extent(&lines).max(extent(&v_isolines)).max(f32::MIN_POSITIVE);
+    lines.extend(v_isolines.into_iter().filter(|line| !collapses(line, tolerance)));
+    lines
+}
+
+// 0,1 split into `samples` intervals, leaving off the 1 when it wraps back to 0
+fn parameter_steps(samples: usize, periodic: bool) -> Vec<f32> {
+    let samples = samples.max(1);
+    let count = if periodic { samples } else { samples + 1 };
+    (0..count).map(|k| k as f32 / samples as f32).collect()
+}
+
+// largest |coordinate| in the grid, what the collapse tolerance is measured against
+pub(crate) fn extent(grid: &[Vec<Vec3f>]) -> f32 {
+    grid.iter().flatten()
+        .flat_map(|p| p.e.iter())
+        .fold(0.0f32, |m, c| if c.is_finite() { m.max(c.abs()) } else { m })
+}
+
+// every point of the row within tolerance of the first
+pub(crate) fn collapses(row: &[Vec3f], tolerance: f32) -> bool {
+    let first = row[0];
+    row.iter().all(|p| {
+        (p.e[0] - first.e[0]).abs() <= tolerance &&
+        (p.e[1] - first.e[1]).abs() <= tolerance &&
+        (p.e[2] - first.e[2]).abs() <= tolerance
+    })
+}
@@ -0,0 +1,394 @@
+/*
+----- The jive quadric -----
+Any surface Ax^2 + By^2 + Cz^2 + Dxy + Eyz + Fxz + Gx + Hy + Iz + J = 0.
+
+    ----- Principal axes -----
+    the quadratic part is the symmetric matrix
+        | A    D/2  F/2 |
+        | D/2  B    E/2 |
+        | F/2  E/2  C   |
+    its eigenvectors are the axes of the surface, and completing the square along
+    each of them finds the center. What is left is one of the canonical equations
+        squares . (x^2, y^2, z^2) + linear * z + constant = 0
+    (parabolic cylinders put their linear term on y) in a local frame where z is
+    the special axis: the axis of a cone, cylinder or paraboloid, the odd sign of a
+    hyperboloid, and x is the normal of the planes in the degenerate cases
+
+    ----- Surfaces -----
+    the canonical shape is sampled in its local frame, then rotated onto the
+    principal axes and moved to the center. Unbounded shapes are cut off
+    at EXTENT, the edge of the -10,10 space
+*/
+
+use core::f32::consts::PI;
+use crate::la::linear_algebra::Vec3f;
+use crate::la::linear_algebra::Mat3x3;
+use crate::jives::jiveparametric::{ParametricSurface, FnSurface};
+use crate::jives::jivesurface::{JiveSurface, SCALE};
+
+// how far unbounded quadrics are drawn from their center
+const EXTENT: f32 = 10.0;
+// relative size below which an eigenvalue or coefficient counts as zero
+const TOLERANCE: f32 = 1.0e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuadricClass {
+    Ellipsoid,
+    HyperboloidOneSheet,
+    HyperboloidTwoSheets,
+    EllipticParaboloid,
+    HyperbolicParaboloid,
+    EllipticCone,
+    EllipticCylinder,
+    HyperbolicCylinder,
+    ParabolicCylinder,
+    // ----- degenerate -----
+    /// a single plane, from a first degree equation
+    Plane,
+    /// two planes meeting in a line
+    IntersectingPlanes,
+    ParallelPlanes,
+    /// a plane counted twice, (ax + by + cz + d)^2 = 0
+    CoincidentPlanes,
+    Line,
+    Point,
+    /// no real points, e.g. x^2 + y^2 + z^2 + 1 = 0
+    Empty,
+}
+
+/// Where a quadric sits and what it looks like in its own frame.
+#[derive(Debug, Clone, Copy)]
+pub struct PrincipalAxes {
+    /// columns are the local x, y, z axes in world space (a proper rotation)
+    pub axes: Mat3x3,
+    /// origin of the local frame in world space
+    pub center: Vec3f,
+    /// coefficients of x^2, y^2, z^2 in the local frame
+    pub squares: [f32; 3],
+    /// coefficient of the first degree term (z, or y for a parabolic cylinder)
+    pub linear: f32,
+    pub constant: f32,
+}
+
+impl PrincipalAxes {
+    /// Local frame coordinates to world coordinates.
+    pub fn to_world(&self, local: &Vec3f) -> Vec3f {
+        let a = &self.axes.e;
+        let l = &local.e;
+        Vec3f::from(self.center.e[0] + a[0]*l[0] + a[1]*l[1] + a[2]*l[2],
+                    self.center.e[1] + a[3]*l[0] + a[4]*l[1] + a[5]*l[2],
+                    self.center.e[2] + a[6]*l[0] + a[7]*l[1] + a[8]*l[2])
+    }
+}
+
+// ----- Quadric -----
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quadric {
+    /// A through J of Ax^2 + By^2 + Cz^2 + Dxy + Eyz + Fxz + Gx + Hy + Iz + J = 0
+    pub coefficients: [f32; 10],
+}
+
+impl Quadric {
+    pub fn new(coefficients: [f32; 10]) -> Result<Quadric, String> {
+        if coefficients.iter().any(|c| !c.is_finite()) {
+            return Err(format!("quadric coefficients must be finite, got {:?}", coefficients));
+        }
+        if coefficients[..9].iter().all(|&c| c == 0.0) {
+            return Err("quadric has no x, y or z terms".to_string());
+        }
+        Ok(Quadric{ coefficients })
+    }
+
+    /// The symmetric matrix of the second degree terms.
+    pub fn quadratic_form(&self) -> Mat3x3 {
+        let [a, b, c, d, e, f, ..] = self.coefficients;
+        Mat3x3{ e: [a,       d / 2.0, f / 2.0,
+                    d / 2.0, b,       e / 2.0,
+                    f / 2.0, e / 2.0, c] }
+    }
+
+    pub fn classify(&self) -> QuadricClass {
+        self.principal_axes().0
+    }
+
+    /// Classifies the quadric and reduces it to its canonical equation.
+    pub fn principal_axes(&self) -> (QuadricClass, PrincipalAxes) {
+        let [.., g, h, i, j] = self.coefficients;
+        let (eigenvalues, eigenvectors) = self.quadratic_form().symmetric_eigen();
+        let column = |k: usize| Vec3f::from(eigenvectors.e[k], eigenvectors.e[3 + k], eigenvectors.e[6 + k]);
+        let columns = [column(0), column(1), column(2)];
+        let linear_terms = Vec3f::from(g, h, i);
+
+        let largest = eigenvalues.iter().fold(0.0f32, |m, l| m.max(l.abs()));
+        let linear_size = linear_terms.length();
+
+        // linear terms in the eigenvector frame
+        let b = columns.map(|column| column.dot(&linear_terms));
+        let nonzero: Vec<usize> = (0..3).filter(|&k| eigenvalues[k].abs() > TOLERANCE * largest).collect();
+        let zero: Vec<usize> = (0..3).filter(|k| !nonzero.contains(k)).collect();
+
+        // complete the square along every axis with a squared term
+        let mut center = [0.0f32; 3];
+        let mut constant = j;
+        let mut constant_size = j.abs();
+        for &k in nonzero.iter() {
+            center[k] = -b[k] / (2.0 * eigenvalues[k]);
+            constant -= b[k] * b[k] / (4.0 * eigenvalues[k]);
+            constant_size += (b[k] * b[k] / (4.0 * eigenvalues[k])).abs();
+        }
+        let constant_is_zero = constant.abs() <= TOLERANCE * constant_size;
+
+        // what is left of the linear part lives in the directions with no squared term
+        let mut linear_dir = Vec3f::zero();
+        for &k in zero.iter() {
+            linear_dir += columns[k] * b[k];
+        }
+        let linear = linear_dir.length();
+        let has_linear = !zero.is_empty() && linear > TOLERANCE * linear_size;
+
+        let mut world_center = columns[0] * center[0] + columns[1] * center[1] + columns[2] * center[2];
+        let squares_of = |order: [usize; 3]| [eigenvalues[order[0]], eigenvalues[order[1]], eigenvalues[order[2]]];
+
+        let (class, x_axis, y_axis, z_axis, squares, linear_coefficient, constant) = match (nonzero.len(), has_linear) {
+            (0, _) => {
+                // Gx + Hy + Iz + J = 0, the normal is local x
+                let normal = linear_terms / linear_size;
+                world_center = normal * (-j / linear_size);
+                let (y_axis, z_axis) = perpendicular_pair(normal);
+                (QuadricClass::Plane, normal, y_axis, z_axis, [0.0; 3], 0.0, 0.0)
+            }
+            (1, true) => {
+                // l x^2 + linear * y = 0 after sliding the center along the linear direction
+                let k = nonzero[0];
+                let y_axis = linear_dir / linear;
+                world_center += y_axis * (-constant / linear);
+                let z_axis = columns[k].cross(&y_axis);
+                (QuadricClass::ParabolicCylinder, columns[k], y_axis, z_axis, [eigenvalues[k], 0.0, 0.0], linear, 0.0)
+            }
+            (1, false) => {
+                let k = nonzero[0];
+                let (y_axis, z_axis) = perpendicular_pair(columns[k]);
+                let class = if constant_is_zero {
+                    QuadricClass::CoincidentPlanes
+                } else if -constant / eigenvalues[k] > 0.0 {
+                    QuadricClass::ParallelPlanes
+                } else {
+                    QuadricClass::Empty
+                };
+                let constant = if constant_is_zero { 0.0 } else { constant };
+                (class, columns[k], y_axis, z_axis, [eigenvalues[k], 0.0, 0.0], 0.0, constant)
+            }
+            (2, true) => {
+                // the axis with no squared term carries the linear term
+                let (p, q, k) = (nonzero[0], nonzero[1], zero[0]);
+                world_center += columns[k] * (-constant / b[k]);
+                let class = if eigenvalues[p] * eigenvalues[q] > 0.0 {
+                    QuadricClass::EllipticParaboloid
+                } else {
+                    QuadricClass::HyperbolicParaboloid
+                };
+                (class, columns[p], columns[q], columns[k], squares_of([p, q, k]), b[k], 0.0)
+            }
+            (2, false) => {
+                let (mut p, mut q, k) = (nonzero[0], nonzero[1], zero[0]);
+                let same_sign = eigenvalues[p] * eigenvalues[q] > 0.0;
+                let class = if constant_is_zero {
+                    if same_sign { QuadricClass::Line } else { QuadricClass::IntersectingPlanes }
+                } else {
+                    let positive = [p, q].iter().filter(|&&n| -eigenvalues[n] / constant > 0.0).count();
+                    match positive {
+                        2 => QuadricClass::EllipticCylinder,
+                        1 => QuadricClass::HyperbolicCylinder,
+                        _ => QuadricClass::Empty,
+                    }
+                };
+                // the hyperbolic cylinder opens along local x
+                if class == QuadricClass::HyperbolicCylinder && -eigenvalues[p] / constant < 0.0 {
+                    std::mem::swap(&mut p, &mut q);
+                }
+                let constant = if constant_is_zero { 0.0 } else { constant };
+                (class, columns[p], columns[q], columns[k], squares_of([p, q, k]), 0.0, constant)
+            }
+            _ => {
+                let (class, odd) = if constant_is_zero {
+                    match odd_sign(eigenvalues) {
+                        Some(odd) => (QuadricClass::EllipticCone, odd),
+                        None => (QuadricClass::Point, 2),
+                    }
+                } else {
+                    // -eigenvalue / constant > 0 are the axes of the ellipse-like sections
+                    let normalized = [-eigenvalues[0] / constant, -eigenvalues[1] / constant, -eigenvalues[2] / constant];
+                    let positive = normalized.iter().filter(|&&n| n > 0.0).count();
+                    match positive {
+                        3 => (QuadricClass::Ellipsoid, 2),
+                        2 => (QuadricClass::HyperboloidOneSheet, odd_sign(normalized).unwrap_or(2)),
+                        1 => (QuadricClass::HyperboloidTwoSheets, odd_sign(normalized).unwrap_or(2)),
+                        _ => (QuadricClass::Empty, 2),
+                    }
+                };
+                let order = [(odd + 1) % 3, (odd + 2) % 3, odd];
+                let constant = if constant_is_zero { 0.0 } else { constant };
+                (class, columns[order[0]], columns[order[1]], columns[order[2]], squares_of(order), 0.0, constant)
+            }
+        };
+
+        // keep the frame right handed, x only ever appears squared or as a plane normal
+        let x_axis = if x_axis.cross(&y_axis).dot(&z_axis) < 0.0 { -x_axis } else { x_axis };
+        let (x, y, z) = (x_axis.e, y_axis.e, z_axis.e);
+        let axes = Mat3x3{ e: [x[0], y[0], z[0],
+                               x[1], y[1], z[1],
+                               x[2], y[2], z[2]] };
+        let principal = PrincipalAxes{ axes, center: world_center, squares,
+                                       linear: linear_coefficient, constant };
+        (class, principal)
+    }
+
+    /// The quadric as a renderable surface, rotated onto its principal axes and
+    /// moved to its center. Points, lines and empty quadrics are an error.
+    pub fn to_surface(&self) -> Result<JiveSurface, String> {
+        let (class, principal) = self.principal_axes();
+        let [sx, sy, sz] = principal.squares;
+        let (k, c) = (principal.linear, principal.constant);
+        // semi axis along a direction of x^2 * square = -constant
+        let semi = |square: f32| (-c / square).abs().sqrt();
+
+        let patches: Vec<LocalPatch> = match class {
+            QuadricClass::Ellipsoid => {
+                let (a, b, c) = (semi(sx), semi(sy), semi(sz));
+                vec![local(move |u, v| [a * u.cos() * v.sin(), b * u.sin() * v.sin(), c * v.cos()],
+                           (0.0, 2.0 * PI), (0.0, PI), true)]
+            }
+            QuadricClass::HyperboloidOneSheet => {
+                let (a, b, c) = (semi(sx), semi(sy), semi(sz));
+                let reach = (EXTENT / c).asinh();
+                vec![local(move |u, v| [a * v.cosh() * u.cos(), b * v.cosh() * u.sin(), c * v.sinh()],
+                           (0.0, 2.0 * PI), (-reach, reach), true)]
+            }
+            QuadricClass::HyperboloidTwoSheets => {
+                let (a, b, c) = (semi(sx), semi(sy), semi(sz));
+                let reach = (EXTENT / c).max(1.0 + TOLERANCE).acosh();
+                let sheet = move |side: f32| local(move |u, v| [a * v.sinh() * u.cos(), b * v.sinh() * u.sin(), side * c * v.cosh()],
+                                                   (0.0, 2.0 * PI), (0.0, reach), true);
+                vec![sheet(1.0), sheet(-1.0)]
+            }
+            QuadricClass::EllipticCone => {
+                // x^2 / a^2 + y^2 / b^2 = z^2, both nappes
+                let (a, b) = ((sz / sx).abs().sqrt(), (sz / sy).abs().sqrt());
+                vec![local(move |u, v| [a * v * u.cos(), b * v * u.sin(), v],
+                           (0.0, 2.0 * PI), (-EXTENT, EXTENT), true)]
+            }
+            QuadricClass::EllipticParaboloid => {
+                // z = (x^2 / a^2 + y^2 / b^2) * direction
+                let (a, b) = ((k / sx).abs().sqrt(), (k / sy).abs().sqrt());
+                let direction = -(sx / k).signum();
+                let reach = EXTENT.sqrt();
+                vec![local(move |u, v| [a * v * u.cos(), b * v * u.sin(), direction * v * v],
+                           (0.0, 2.0 * PI), (0.0, reach), true)]
+            }
+            QuadricClass::HyperbolicParaboloid => {
+                // z = -(sx x^2 + sy y^2) / k as a graph over a window that keeps |z| near EXTENT
+                let (px, py) = (-sx / k, -sy / k);
+                let (x_reach, y_reach) = ((EXTENT / px.abs()).sqrt().min(EXTENT), (EXTENT / py.abs()).sqrt().min(EXTENT));
+                vec![local(move |u, v| {
+                    let (x, y) = (u * x_reach, v * y_reach);
+                    [x, y, px * x * x + py * y * y]
+                }, (-1.0, 1.0), (-1.0, 1.0), false)]
+            }
+            QuadricClass::EllipticCylinder => {
+                let (a, b) = (semi(sx), semi(sy));
+                vec![local(move |u, v| [a * u.cos(), b * u.sin(), v],
+                           (0.0, 2.0 * PI), (-EXTENT, EXTENT), true)]
+            }
+            QuadricClass::HyperbolicCylinder => {
+                // x^2 / a^2 - y^2 / b^2 = 1, one patch per branch
+                let (a, b) = (semi(sx), semi(sy));
+                let reach = (EXTENT / b).asinh();
+                let branch = move |side: f32| local(move |u, v| [side * a * u.cosh(), b * u.sinh(), v],
+                                                    (-reach, reach), (-EXTENT, EXTENT), false);
+                vec![branch(1.0), branch(-1.0)]
+            }
+            QuadricClass::ParabolicCylinder => {
+                // y = -sx x^2 / k
+                let p = -sx / k;
+                let reach = (EXTENT / p.abs()).sqrt().min(EXTENT);
+                vec![local(move |u, v| [u, p * u * u, v], (-reach, reach), (-EXTENT, EXTENT), false)]
+            }
+            QuadricClass::Plane | QuadricClass::CoincidentPlanes => {
+                vec![plane_patch(0.0)]
+            }
+            QuadricClass::ParallelPlanes => {
+                let offset = semi(sx);
+                vec![plane_patch(offset), plane_patch(-offset)]
+            }
+            QuadricClass::IntersectingPlanes => {
+                // sx x^2 + sy y^2 = 0  ->  y = +-slope x, each plane spanned by its line and z
+                let angle = (-sx / sy).abs().sqrt().atan();
+                let sheet = move |angle: f32| local(move |u, v| [u * angle.cos(), u * angle.sin(), v],
+                                                    (-EXTENT, EXTENT), (-EXTENT, EXTENT), false);
+                vec![sheet(angle), sheet(-angle)]
+            }
+            QuadricClass::Line | QuadricClass::Point | QuadricClass::Empty => {
+                return Err(format!("{:?} quadric has no surface to draw", class));
+            }
+        };
+
+        let patches: Vec<Box<dyn ParametricSurface>> = patches.into_iter()
+            .map(|patch| Box::new(Placed{ patch, principal }) as Box<dyn ParametricSurface>)
+            .collect();
+        JiveSurface::from_patches(patches, 64, 32)
+    }
+}
+
+// ----- local frame patches -----
+
+type LocalPatch = FnSurface<Box<dyn Fn(f32, f32) -> Vec3f>>;
+
+fn local<F: Fn(f32, f32) -> [f32; 3] + 'static>(f: F, u_domain: (f32, f32), v_domain: (f32, f32), u_periodic: bool) -> LocalPatch {
+    let function: Box<dyn Fn(f32, f32) -> Vec3f> = Box::new(move |u, v| Vec3f::new(f(u, v)));
+    FnSurface::new(function, u_domain, v_domain).periodic(u_periodic, false)
+}
+
+// the plane local x = offset
+fn plane_patch(offset: f32) -> LocalPatch {
+    local(move |u, v| [offset, u, v], (-EXTENT, EXTENT), (-EXTENT, EXTENT), false)
+}
+
+// a local frame patch moved onto the principal axes, then scaled like every other surface
+struct Placed {
+    patch: LocalPatch,
+    principal: PrincipalAxes,
+}
+
+impl ParametricSurface for Placed {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        self.principal.to_world(&self.patch.evaluate(u, v)) * SCALE
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        self.patch.u_domain()
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        self.patch.v_domain()
+    }
+    fn u_periodic(&self) -> bool {
+        self.patch.u_periodic()
+    }
+}
+
+// two unit vectors completing `normal` (unit) to a right handed frame
+fn perpendicular_pair(normal: Vec3f) -> (Vec3f, Vec3f) {
+    // start from the world axis least aligned with the normal
+    let helper = if normal.e[0].abs() < 0.9 { Vec3f::from(1.0, 0.0, 0.0) } else { Vec3f::from(0.0, 1.0, 0.0) };
+    let first = normal.cross(&helper).normalize();
+    (first, normal.cross(&first))
+}
+
+// index of the one value whose sign differs from the other two
+fn odd_sign(values: [f32; 3]) -> Option<usize> {
+    let positive: Vec<usize> = (0..3).filter(|&k| values[k] > 0.0).collect();
+    match positive.len() {
+        1 => Some(positive[0]),
+        2 => (0..3).find(|k| !positive.contains(k)),
+        _ => None,
+    }
+}
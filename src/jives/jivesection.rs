@@ -0,0 +1,180 @@
+/*
+----- The jive section -----
+Cutting the double cone z^2 = steepness^2 (x^2 + y^2) with the plane ax + by + cz = d,
+without going through frdim.
+
+    ----- The curve -----
+    every line through the apex along w(t) = (cos t, sin t, steepness) meets the plane at
+    d w / (n . w), so t -> d w(t) / (n . w(t)) runs over the whole section. Where n . w
+    goes to zero the curve runs off to infinity, those stretches are cut off 20 units out
+    and what is left is one closed loop (ellipse) or one or two arcs (parabola, hyperbola)
+
+    ----- Classification -----
+    the eccentricity is cos(angle between plane and axis) / cos(half angle of the cone),
+    a plane through the apex gives the degenerate point, line or line pair instead
+
+    ----- Coefficients -----
+    Ax^2 + Bxy + Cy^2 + Dx + Ey + F = 0 in the plane's own coordinates: x along e1 and y
+    along e2 from the plane's closest point to the origin, in -10,10 units
+*/
+
+use std::f32::consts::PI;
+use crate::la::linear_algebra::Vec3f;
+use crate::jives::jivecurve::{JiveCurve, CONIC_REACH};
+use crate::jives::jivesurface::{SurfaceKind, SCALE};
+
+// eccentricity within this of 1 is a parabola, a plane within this of the apex goes through it
+const TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConicClass {
+    Ellipse,
+    Parabola,
+    Hyperbola,
+    /// the plane only touches the apex
+    Point,
+    /// the plane is tangent to the cone along one line
+    Line,
+    /// the plane cuts through the apex
+    LinePair,
+    /// only from general coefficients, a plane never cuts a cone like this
+    ParallelLines,
+    /// only from general coefficients, no real points (x^2 + y^2 + 1 = 0)
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConicSection {
+    pub class: ConicClass,
+    /// A..F of Ax^2 + Bxy + Cy^2 + Dx + Ey + F = 0 in plane coordinates
+    pub coefficients: [f32; 6],
+    /// the plane's closest point to the origin, where plane coordinates start
+    pub origin: Vec3f,
+    pub e1: Vec3f,
+    pub e2: Vec3f,
+    steepness: f32,
+    normal: [f32; 3],
+    d: f32,
+}
+
+impl ConicSection {
+    /// Intersects the double cone a `SurfaceKind::Cone` is the upper half of with a
+    /// `SurfaceKind::Plane`.
+    pub fn new(cone: &SurfaceKind, plane: &SurfaceKind) -> Result<ConicSection, String> {
+        cone.validate()?;
+        plane.validate()?;
+        let steepness = match *cone {
+            SurfaceKind::Cone { steepness, .. } => steepness,
+            _ => return Err(format!("{cone:?} is not a cone")),
+        };
+        let (a, b, c, d) = match *plane {
+            SurfaceKind::Plane { a, b, c, d, .. } => (a, b, c, d),
+            _ => return Err(format!("{plane:?} is not a plane")),
+        };
+        let (origin, e1, e2) = plane.plane_basis()?;
+
+        // x^2 + y^2 - z^2 / s^2 as a bilinear form
+        let form = |p: &Vec3f, q: &Vec3f| p.e[0] * q.e[0] + p.e[1] * q.e[1] - p.e[2] * q.e[2] / (steepness * steepness);
+        let coefficients = [
+            form(&e1, &e1), 2.0 * form(&e1, &e2), form(&e2, &e2),
+            2.0 * form(&origin, &e1), 2.0 * form(&origin, &e2), form(&origin, &origin),
+        ];
+
+        let mut section = ConicSection{ class: ConicClass::Point, coefficients, origin, e1, e2,
+                                        steepness, normal: [a, b, c], d };
+        let eccentricity = section.eccentricity();
+        let through_apex = origin.magnitude() < TOLERANCE;
+        section.class = match (through_apex, eccentricity) {
+            (false, e) if e < 1.0 - TOLERANCE => ConicClass::Ellipse,
+            (false, e) if e <= 1.0 + TOLERANCE => ConicClass::Parabola,
+            (false, _) => ConicClass::Hyperbola,
+            (true, e) if e < 1.0 - TOLERANCE => ConicClass::Point,
+            (true, e) if e <= 1.0 + TOLERANCE => ConicClass::Line,
+            (true, _) => ConicClass::LinePair,
+        };
+        Ok(section)
+    }
+
+    /// 0 for a circle, below 1 for an ellipse, 1 for a parabola and above for a hyperbola.
+    pub fn eccentricity(&self) -> f32 {
+        let [a, b, c] = self.normal;
+        let cos_plane = (a * a + b * b).sqrt() / (a * a + b * b + c * c).sqrt();
+        let cos_cone = self.steepness / (1.0 + self.steepness * self.steepness).sqrt();
+        cos_plane / cos_cone
+    }
+
+    /// Plane coordinates to a point in -10,10 space.
+    pub fn to_world(&self, x: f32, y: f32) -> Vec3f {
+        let p = |k: usize| self.origin.e[k] + x * self.e1.e[k] + y * self.e2.e[k];
+        Vec3f::from(p(0), p(1), p(2))
+    }
+
+    /// The section drawn as curves in the plane: one loop, one or two arcs, or the
+    /// apex and lines of a degenerate section.
+    pub fn curves(&self) -> Result<Vec<JiveCurve>, String> {
+        let s = self.steepness;
+        let [a, b, c] = self.normal;
+        // n . w(t) = r cos(t - phi) + c s
+        let r = (a * a + b * b).sqrt();
+        let phi = b.atan2(a);
+        let w = move |t: f32| [t.cos(), t.sin(), s];
+        let reach = CONIC_REACH / (1.0 + s * s).sqrt();
+
+        match self.class {
+            ConicClass::Point => {
+                let apex = Vec3f::from(0.0, 0.0, 0.0);
+                return Ok(vec![JiveCurve::line(apex, apex)?]);
+            }
+            ConicClass::Line | ConicClass::LinePair => {
+                // the generators lying in the plane, cos(t - phi) = -c s / r
+                let offset = (-c * s / r).clamp(-1.0, 1.0).acos();
+                let mut angles = vec![phi + offset];
+                if self.class == ConicClass::LinePair {
+                    angles.push(phi - offset);
+                }
+                return angles.into_iter().map(|t| {
+                    let [x, y, z] = w(t);
+                    JiveCurve::line(Vec3f::from(-reach * x, -reach * y, -reach * z), Vec3f::from(reach * x, reach * y, reach * z))
+                }).collect();
+            }
+            _ => {}
+        }
+
+        // keep |n . w| >= k, which keeps the points within CONIC_REACH of the cone's apex
+        let d = self.d;
+        let k = d.abs() * (1.0 + s * s).sqrt() / CONIC_REACH;
+        let mut arcs: Vec<(f32, f32)> = Vec::new();
+        if r < f32::EPSILON {
+            if (c * s).abs() >= k {
+                arcs.push((-PI, PI));
+            }
+        } else {
+            // n . w >= k around t = phi, n . w <= -k around t = phi + pi
+            let upper = (k - c * s) / r;
+            if upper <= -1.0 {
+                arcs.push((-PI, PI));
+            } else if upper < 1.0 {
+                let half = upper.acos();
+                arcs.push((-half, half));
+            }
+            let lower = (-k - c * s) / r;
+            if lower >= 1.0 {
+                arcs.push((-PI, PI));
+            } else if lower > -1.0 {
+                let half = PI - lower.acos();
+                arcs.push((PI - half, PI + half));
+            }
+        }
+
+        arcs.into_iter().map(|(start, end)| {
+            let point = move |psi: f32| {
+                let t = psi + phi;
+                let [x, y, z] = w(t);
+                let along = d / (a * x + b * y + c * z);
+                Vec3f::from(SCALE * along * x, SCALE * along * y, SCALE * along * z)
+            };
+            let curve = JiveCurve::new(point, (start, end), 180)?;
+            Ok(curve.closed(end - start >= 2.0 * PI))
+        }).collect()
+    }
+}
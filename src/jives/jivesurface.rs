@@ -25,216 +25,816 @@ pub struct JiveSurface{
 use core::f32::consts::PI;
 use crate::la::linear_algebra::Vec3f;
 use crate::la::linear_algebra::Mat3x3;
+use crate::la::linear_algebra::Mat4x4;
+use crate::la::quaternion::Quat;
+use crate::jives::jivemesh::Mesh;
+use crate::jives::jiveparametric::{ParametricSurface, Sampling, sample_grid, sample_points, isolines, normal_from_partials};
+use crate::util::lighting::Material;
+use crate::util::shader::Shader;
 
-const SPHERE: u8 = 1;
-const ELLIPSOID: u8 = 2;
-const HYPERBOLOID: u8 = 3;
-const PARABOLOID: u8 = 4;
+// surface flags as handed out by frdim (conic_section.conic_type())
+pub const SPHERE: u8 = 1;
+pub const ELLIPSOID: u8 = 2;
+pub const HYPERBOLOID: u8 = 3;
+pub const PARABOLOID: u8 = 4;
+pub const PLANE: u8 = 5;
+pub const CONE: u8 = 6;
 
+// everything is built in -10,10 space and scaled down to -0.5,0.5
+pub(crate) const SCALE: f32 = 1.0 / 20.0;
 
-const PLANE: u8 = 5;
-const CONE: u8 = 6;
+/// Half the side of the square drawn for `SurfaceKind::plane`, enough to cross the whole -10,10 space.
+pub const PLANE_EXTENT: f32 = 10.0;
 
+// ----- SurfaceKind -----
 
-pub struct JiveSurface{
-    surface_flag: u8,
-    surface_coefficients: [f32; 6],
-    pub surface_transformation: Mat3x3,
+/// The shape a `JiveSurface` samples, along with the parameters that shape needs.
+/// Build these with the validated constructors (`SurfaceKind::sphere` etc.) so
+/// nonsense like a negative radius is caught before anything is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurfaceKind {
+    /// x^2 + y^2 + z^2 = radius^2
+    Sphere { radius: f32 },
+    /// x^2/a^2 + y^2/b^2 + z^2/c^2 = 1
+    Ellipsoid { a: f32, b: f32, c: f32 },
+    /// x^2/a^2 + y^2/b^2 - z^2/c^2 = 1, one sheet
+    Hyperboloid { a: f32, b: f32, c: f32 },
+    /// z^2/c^2 - x^2/a^2 - y^2/b^2 = 1, both sheets
+    HyperboloidTwoSheets { a: f32, b: f32, c: f32 },
+    /// z = x^2/a^2 + y^2/b^2
+    Paraboloid { a: f32, b: f32 },
+    /// z = x^2/a^2 - y^2/b^2, the saddle
+    HyperbolicParaboloid { a: f32, b: f32 },
+    /// ax + by + cz = d, drawn as a square 2 * extent wide centered on the
+    /// point of the plane closest to the origin
+    Plane { a: f32, b: f32, c: f32, d: f32, extent: f32 },
+    /// z = steepness * sqrt(x^2 + y^2), cut off at z = height
+    Cone { steepness: f32, height: f32 },
+    /// x^2/a^2 + y^2/b^2 = z^2, both nappes, from z = -height to z = height
+    EllipticCone { a: f32, b: f32, height: f32 },
+    /// x^2/a^2 + y^2/b^2 = 1, from z = -height/2 to z = height/2
+    Cylinder { a: f32, b: f32, height: f32 },
+    /// (sqrt(x^2 + y^2) - major_radius)^2 + z^2 = minor_radius^2
+    Torus { major_radius: f32, minor_radius: f32 },
+}
+
+impl SurfaceKind {
+    pub fn sphere(radius: f32) -> Result<SurfaceKind, String> {
+        positive("sphere radius", radius)?;
+        Ok(SurfaceKind::Sphere { radius })
+    }
+    pub fn ellipsoid(a: f32, b: f32, c: f32) -> Result<SurfaceKind, String> {
+        positive("ellipsoid a", a)?;
+        positive("ellipsoid b", b)?;
+        positive("ellipsoid c", c)?;
+        Ok(SurfaceKind::Ellipsoid { a, b, c })
+    }
+    pub fn hyperboloid(a: f32, b: f32, c: f32) -> Result<SurfaceKind, String> {
+        positive("hyperboloid a", a)?;
+        positive("hyperboloid b", b)?;
+        positive("hyperboloid c", c)?;
+        Ok(SurfaceKind::Hyperboloid { a, b, c })
+    }
+    pub fn hyperboloid_two_sheets(a: f32, b: f32, c: f32) -> Result<SurfaceKind, String> {
+        positive("hyperboloid a", a)?;
+        positive("hyperboloid b", b)?;
+        positive("hyperboloid c", c)?;
+        Ok(SurfaceKind::HyperboloidTwoSheets { a, b, c })
+    }
+    pub fn paraboloid(a: f32, b: f32) -> Result<SurfaceKind, String> {
+        positive("paraboloid a", a)?;
+        positive("paraboloid b", b)?;
+        Ok(SurfaceKind::Paraboloid { a, b })
+    }
+    pub fn hyperbolic_paraboloid(a: f32, b: f32) -> Result<SurfaceKind, String> {
+        positive("hyperbolic paraboloid a", a)?;
+        positive("hyperbolic paraboloid b", b)?;
+        Ok(SurfaceKind::HyperbolicParaboloid { a, b })
+    }
+    pub fn plane(a: f32, b: f32, c: f32, d: f32) -> Result<SurfaceKind, String> {
+        SurfaceKind::plane_with_extent(a, b, c, d, PLANE_EXTENT)
+    }
+    /// A plane patch reaching `extent` from its center in each in-plane direction.
+    pub fn plane_with_extent(a: f32, b: f32, c: f32, d: f32, extent: f32) -> Result<SurfaceKind, String> {
+        positive("plane extent", extent)?;
+        plane_frame(a, b, c, d)?;
+        Ok(SurfaceKind::Plane { a, b, c, d, extent })
+    }
+    pub fn cone(steepness: f32, height: f32) -> Result<SurfaceKind, String> {
+        positive("cone steepness", steepness)?;
+        positive("cone height", height)?;
+        Ok(SurfaceKind::Cone { steepness, height })
+    }
+    /// Double napped cone, circular when a == b.
+    pub fn elliptic_cone(a: f32, b: f32, height: f32) -> Result<SurfaceKind, String> {
+        positive("elliptic cone a", a)?;
+        positive("elliptic cone b", b)?;
+        positive("elliptic cone height", height)?;
+        Ok(SurfaceKind::EllipticCone { a, b, height })
+    }
+    pub fn cylinder(radius: f32, height: f32) -> Result<SurfaceKind, String> {
+        SurfaceKind::elliptic_cylinder(radius, radius, height)
+    }
+    pub fn elliptic_cylinder(a: f32, b: f32, height: f32) -> Result<SurfaceKind, String> {
+        positive("cylinder a", a)?;
+        positive("cylinder b", b)?;
+        positive("cylinder height", height)?;
+        Ok(SurfaceKind::Cylinder { a, b, height })
+    }
+    pub fn torus(major_radius: f32, minor_radius: f32) -> Result<SurfaceKind, String> {
+        positive("torus major radius", major_radius)?;
+        positive("torus minor radius", minor_radius)?;
+        Ok(SurfaceKind::Torus { major_radius, minor_radius })
+    }
 
-    vmin: usize, vmax: usize, vstep: usize,
-    umin: usize, umax: usize, ustep: usize,
+    /// Runs the constructor checks again, for a kind that was built by hand.
+    pub fn validate(&self) -> Result<(), String> {
+        let rebuilt = match *self {
+            SurfaceKind::Sphere { radius } => SurfaceKind::sphere(radius),
+            SurfaceKind::Ellipsoid { a, b, c } => SurfaceKind::ellipsoid(a, b, c),
+            SurfaceKind::Hyperboloid { a, b, c } => SurfaceKind::hyperboloid(a, b, c),
+            SurfaceKind::HyperboloidTwoSheets { a, b, c } => SurfaceKind::hyperboloid_two_sheets(a, b, c),
+            SurfaceKind::Paraboloid { a, b } => SurfaceKind::paraboloid(a, b),
+            SurfaceKind::HyperbolicParaboloid { a, b } => SurfaceKind::hyperbolic_paraboloid(a, b),
+            SurfaceKind::Plane { a, b, c, d, extent } => SurfaceKind::plane_with_extent(a, b, c, d, extent),
+            SurfaceKind::Cone { steepness, height } => SurfaceKind::cone(steepness, height),
+            SurfaceKind::EllipticCone { a, b, height } => SurfaceKind::elliptic_cone(a, b, height),
+            SurfaceKind::Cylinder { a, b, height } => SurfaceKind::elliptic_cylinder(a, b, height),
+            SurfaceKind::Torus { major_radius, minor_radius } => SurfaceKind::torus(major_radius, minor_radius),
+        };
+        rebuilt.map(|_| ())
+    }
 
-}
-impl JiveSurface{
+    /// The plane's center (its closest point to the origin) and an orthonormal
+    /// in-plane basis e1, e2 with e1 x e2 along (a, b, c), all in -10,10 units.
+    pub fn plane_basis(&self) -> Result<(Vec3f, Vec3f, Vec3f), String> {
+        match *self {
+            SurfaceKind::Plane { a, b, c, d, .. } => plane_frame(a, b, c, d),
+            _ => Err(format!("{self:?} is not a plane")),
+        }
+    }
 
-    pub fn new(flag: u8, coefficients: [f32; 6]) -> JiveSurface {
-        let identity: Mat3x3 = Mat3x3::identity();
+    /// Reads the old flag + `[f32; 6]` pair coming out of frdim, sized the way the old
+    /// flag code drew it. The coefficients are Ax^2 + Bxy + Cy^2 + Dx + Ey + F, except for
+    /// the plane (a, b, c, d) and the cone (steepness, height). Unknown flags are an error.
+    /// The hyperboloid comes out in its own frame (axis along z), `JiveSurface::from_flag`
+    /// also stands it along y and keeps the upper half like the old code did.
+    pub fn from_flag(flag: u8, coefficients: [f32; 6]) -> Result<SurfaceKind, String> {
+        let [a, b, c, d, _, f] = coefficients;
+        // the old solve halved the radius and the A, C axes
+        let half_root_f = f.abs().sqrt() / 2.0;
         match flag {
-            SPHERE => {
-                JiveSurface{surface_flag: SPHERE, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 180, vstep: 30, umin: 0, umax: 360, ustep: 4} 
-            }
+            SPHERE => SurfaceKind::sphere(half_root_f),
             ELLIPSOID => {
-                JiveSurface{surface_flag: ELLIPSOID, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 180, vstep: 10, umin: 0, umax: 360, ustep: 4}
+                let (half_a, half_c) = (a.abs() / 2.0, c.abs() / 2.0);
+                SurfaceKind::ellipsoid(half_a * half_root_f, half_c * half_root_f, half_a.min(half_c) * half_root_f)
             }
-            HYPERBOLOID => {
-                JiveSurface{surface_flag: HYPERBOLOID, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 10, vstep: 1, umin: 0, umax: 360, ustep: 4}
-            }
-            PARABOLOID => {
-                JiveSurface{surface_flag: PARABOLOID, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 10, vstep: 1, umin: 0, umax: 360, ustep: 4}
+            // the old solve scaled the A, C axes twice and left the sinh along the axis unscaled
+            HYPERBOLOID => SurfaceKind::hyperboloid(a.abs() * SCALE, c.abs() * SCALE, 1.0),
+            PARABOLOID => SurfaceKind::paraboloid(a, c),
+            PLANE => SurfaceKind::plane(a, b, c, d),
+            CONE => SurfaceKind::cone(a, b),
+            _ => Err(format!("unknown surface flag {flag}")),
+        }
+    }
+}
+
+// the built in shapes are parametric surfaces like any other,
+// u and v are in the same units JiveSurface::solve expects
+impl ParametricSurface for SurfaceKind {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        let (x,y,z) = JiveSurface::solve(self, v, u);
+        Vec3f::from(x,y,z)
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        match *self {
+            SurfaceKind::Plane { extent, .. } => (-extent, extent),
+            _ => (0.0, 360.0),
+        }
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        match *self {
+            SurfaceKind::Sphere { .. } | SurfaceKind::Ellipsoid { .. } => (0.0, 180.0),
+            // both halves of the sheet, v / 10 is the hyperbolic angle
+            SurfaceKind::Hyperboloid { .. } => (-10.0, 10.0),
+            // keeps the saddle inside -10,10
+            SurfaceKind::HyperbolicParaboloid { .. } => (0.0, 10.0f32.sqrt()),
+            SurfaceKind::Plane { extent, .. } => (-extent, extent),
+            // v is z for these two
+            SurfaceKind::EllipticCone { height, .. } => (-height, height),
+            SurfaceKind::Cylinder { height, .. } => (-height / 2.0, height / 2.0),
+            SurfaceKind::Torus { .. } => (0.0, 360.0),
+            _ => (0.0, 10.0),
+        }
+    }
+    fn u_periodic(&self) -> bool {
+        !matches!(self, SurfaceKind::Plane { .. })
+    }
+    fn v_periodic(&self) -> bool {
+        matches!(self, SurfaceKind::Torus { .. })
+    }
+    // differentiates JiveSurface::solve, u and v are in its units (degrees for angles)
+    fn partials(&self, u: f32, v: f32) -> (Vec3f, Vec3f) {
+        let per_degree = PI / 180.0;
+        let theta = JiveSurface::d2rad(u);
+        let (sin, cos) = theta.sin_cos();
+        let (du, dv) = match *self {
+            SurfaceKind::Cone { steepness, height } => {
+                let k = height / (200.0 * steepness);
+                ([-k * v * sin * per_degree, k * v * cos * per_degree, 0.0], [k * cos, k * sin, k * steepness])
             }
-            PLANE => {
-                JiveSurface{surface_flag: PLANE, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 10, vstep: 1, umin: 0, umax: 10, ustep: 1}
+            SurfaceKind::Plane { .. } => match self.plane_basis() {
+                Ok((_, e1, e2)) => (e1.e.map(|x| SCALE * x), e2.e.map(|x| SCALE * x)),
+                Err(_) => ([0.0; 3], [0.0; 3]),
+            },
+            SurfaceKind::Sphere { radius } => ellipsoid_partials(radius, radius, radius, u, v),
+            SurfaceKind::Ellipsoid { a, b, c } => ellipsoid_partials(a, b, c, u, v),
+            SurfaceKind::Hyperboloid { a, b, c } => {
+                let (sinh, cosh) = ((v / 10.0).sinh(), (v / 10.0).cosh());
+                (
+                    [-SCALE * a * cosh * sin * per_degree, SCALE * b * cosh * cos * per_degree, 0.0],
+                    [SCALE * a * sinh * cos / 10.0, SCALE * b * sinh * sin / 10.0, SCALE * c * cosh / 10.0],
+                )
             }
-            CONE => { 
-                JiveSurface{surface_flag: CONE, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 10, vstep: 1, umin: 0, umax: 360, ustep: 1} 
+            SurfaceKind::HyperboloidTwoSheets { a, b, c } => {
+                let (sinh, cosh) = ((v / 10.0).sinh(), (v / 10.0).cosh());
+                (
+                    [-SCALE * a * sinh * sin * per_degree, SCALE * b * sinh * cos * per_degree, 0.0],
+                    [SCALE * a * cosh * cos / 10.0, SCALE * b * cosh * sin / 10.0, SCALE * c * sinh / 10.0],
+                )
             }
-            _ => {
-                JiveSurface{surface_flag: SPHERE, surface_coefficients: coefficients, surface_transformation: identity,
-                            vmin: 0, vmax: 180, vstep: 30, umin: 0, umax: 360, ustep: 4} }
+            SurfaceKind::Paraboloid { a, b } => (
+                [-SCALE * a * v * sin * per_degree, SCALE * b * v * cos * per_degree, 0.0],
+                [SCALE * a * cos, SCALE * b * sin, SCALE * 2.0 * v],
+            ),
+            SurfaceKind::HyperbolicParaboloid { a, b } => (
+                [
+                    -SCALE * a * v * sin * per_degree,
+                    SCALE * b * v * cos * per_degree,
+                    -SCALE * 2.0 * v * v * (2.0 * theta).sin() * per_degree,
+                ],
+                [SCALE * a * cos, SCALE * b * sin, SCALE * 2.0 * v * (2.0 * theta).cos()],
+            ),
+            SurfaceKind::EllipticCone { a, b, .. } => (
+                [-SCALE * a * v * sin * per_degree, SCALE * b * v * cos * per_degree, 0.0],
+                [SCALE * a * cos, SCALE * b * sin, SCALE],
+            ),
+            SurfaceKind::Cylinder { a, b, .. } => (
+                [-SCALE * a * sin * per_degree, SCALE * b * cos * per_degree, 0.0],
+                [0.0, 0.0, SCALE],
+            ),
+            SurfaceKind::Torus { major_radius, minor_radius } => {
+                let (sin_phi, cos_phi) = JiveSurface::d2rad(v).sin_cos();
+                let ring = major_radius + minor_radius * cos_phi;
+                (
+                    [-SCALE * ring * sin * per_degree, SCALE * ring * cos * per_degree, 0.0],
+                    [
+                        -SCALE * minor_radius * sin_phi * cos * per_degree,
+                        -SCALE * minor_radius * sin_phi * sin * per_degree,
+                        SCALE * minor_radius * cos_phi * per_degree,
+                    ],
+                )
             }
+        };
+        (Vec3f::new(du), Vec3f::new(dv))
+    }
+    // du x dv, except that spheres and ellipsoids are parametrized inside out
+    fn normal(&self, u: f32, v: f32) -> Vec3f {
+        let n = normal_from_partials(self, u, v);
+        match self {
+            SurfaceKind::Sphere { .. } | SurfaceKind::Ellipsoid { .. } => Vec3f::from(-n.e[0], -n.e[1], -n.e[2]),
+            _ => n,
+        }
+    }
+}
+
+// u = psi around z, v = theta down from the +z pole, both in degrees
+fn ellipsoid_partials(a: f32, b: f32, c: f32, u: f32, v: f32) -> ([f32; 3], [f32; 3]) {
+    let per_degree = PI / 180.0;
+    let (sin_psi, cos_psi) = JiveSurface::d2rad(u).sin_cos();
+    let (sin_theta, cos_theta) = JiveSurface::d2rad(v).sin_cos();
+    let k = SCALE * per_degree;
+    (
+        [-k * a * sin_psi * sin_theta, k * b * cos_psi * sin_theta, 0.0],
+        [k * a * cos_psi * cos_theta, k * b * sin_psi * cos_theta, -k * c * sin_theta],
+    )
+}
+
+// the lower sheet of a two sheet hyperboloid, the upper one mirrored through z = 0
+struct LowerSheet(SurfaceKind);
+
+impl ParametricSurface for LowerSheet {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        let upper = self.0.evaluate(u, v);
+        Vec3f::from(upper.e[0], upper.e[1], -upper.e[2])
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        self.0.u_domain()
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        self.0.v_domain()
+    }
+    fn u_periodic(&self) -> bool {
+        self.0.u_periodic()
+    }
+    fn partials(&self, u: f32, v: f32) -> (Vec3f, Vec3f) {
+        let (du, dv) = self.0.partials(u, v);
+        (Vec3f::from(du.e[0], du.e[1], -du.e[2]), Vec3f::from(dv.e[0], dv.e[1], -dv.e[2]))
+    }
+    // the mirror image of the upper sheet's normal, so both face away from their bowl
+    fn normal(&self, u: f32, v: f32) -> Vec3f {
+        let upper = self.0.normal(u, v);
+        Vec3f::from(upper.e[0], upper.e[1], -upper.e[2])
+    }
+}
+
+// a plane patch with its frame worked out once, SurfaceKind works it out at every point
+struct PlanePatch {
+    center: Vec3f,
+    e1: Vec3f,
+    e2: Vec3f,
+    extent: f32,
+}
+
+impl ParametricSurface for PlanePatch {
+    fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+        (self.center + self.e1 * u + self.e2 * v) * SCALE
+    }
+    fn u_domain(&self) -> (f32, f32) {
+        (-self.extent, self.extent)
+    }
+    fn v_domain(&self) -> (f32, f32) {
+        (-self.extent, self.extent)
+    }
+    fn partials(&self, _u: f32, _v: f32) -> (Vec3f, Vec3f) {
+        (self.e1 * SCALE, self.e2 * SCALE)
+    }
+}
+
+// the pieces a built in shape is drawn with, a kind that fails validate has none
+fn kind_patches(kind: SurfaceKind) -> Result<Vec<Box<dyn ParametricSurface>>, String> {
+    kind.validate()?;
+    Ok(match kind {
+        SurfaceKind::HyperboloidTwoSheets { .. } => vec![Box::new(kind), Box::new(LowerSheet(kind))],
+        SurfaceKind::Plane { a, b, c, d, extent } => {
+            let (center, e1, e2) = plane_frame(a, b, c, d)?;
+            vec![Box::new(PlanePatch{ center, e1, e2, extent })]
+        }
+        _ => vec![Box::new(kind)],
+    })
+}
+
+// center d n / |n|^2, then e1 from whichever axis is furthest from the normal
+fn plane_frame(a: f32, b: f32, c: f32, d: f32) -> Result<(Vec3f, Vec3f, Vec3f), String> {
+    if !(a.is_finite() && b.is_finite() && c.is_finite() && d.is_finite()) {
+        return Err(format!("plane coefficients must be finite, got {a}, {b}, {c}, {d}"));
+    }
+    let length_squared = a * a + b * b + c * c;
+    if length_squared == 0.0 || !length_squared.is_normal() {
+        return Err(format!("plane normal ({a}, {b}, {c}) is degenerate"));
+    }
+    let normal = Vec3f::from(a, b, c).normalize();
+    let center = Vec3f::from(a * d / length_squared, b * d / length_squared, c * d / length_squared);
+
+    let n = normal.e;
+    let mut helper = [0.0f32; 3];
+    let furthest = (0..3).fold(0, |best, k| if n[k].abs() < n[best].abs() { k } else { best });
+    helper[furthest] = 1.0;
+    // gram schmidt the helper axis against the normal, then e2 = n x e1 so e1 x e2 = n
+    let along = helper[furthest] * n[furthest];
+    let e1 = Vec3f::from(helper[0] - along * n[0], helper[1] - along * n[1], helper[2] - along * n[2]).normalize();
+    let e2 = normal.cross(&e1);
+    Ok((center, e1, e2))
+}
+
+pub(crate) fn positive(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be positive and finite, got {value}"))
+    }
+}
+
+// ----- JiveSurface -----
+
+// what a JiveSurface is made of
+enum Geometry {
+    // one or more pieces sharing a parameter domain (the two sheets of a hyperboloid)
+    Patches(Vec<Box<dyn ParametricSurface>>),
+    // an already triangulated surface, e.g. a polygonized implicit surface
+    Triangles(Mesh),
+}
+
+pub struct JiveSurface{
+    geometry: Geometry,
+    kind: Option<SurfaceKind>,
+    pub surface_transformation: Mat3x3,
+    /// turned by the rotate_ methods, applied before surface_transformation
+    pub surface_orientation: Quat,
+    /// where the surface sits in the scene, applied after surface_transformation
+    pub surface_placement: Mat4x4,
+    /// how it takes the light in render_jive_shaded
+    pub surface_material: Material,
+    /// its colors, None for the defaults: Position for points, the material's base color when lit
+    pub surface_shader: Option<Box<dyn Shader>>,
+    /// handed to the shader with every fragment
+    pub surface_id: usize,
+
+    sampling: Sampling,
+
+}
+impl JiveSurface{
+
+    /// A built in shape sampled over its whole domain at its default resolution.
+    /// A kind built by hand that fails `SurfaceKind::validate` (a plane with
+    /// a = b = c = 0) is an error.
+    pub fn new(kind: SurfaceKind) -> Result<JiveSurface, String> {
+        let (u_samples, v_samples) = Self::default_samples(&kind);
+        let sampling = Sampling::new(&kind, u_samples, v_samples);
+        Ok(JiveSurface{geometry: Geometry::Patches(kind_patches(kind)?), kind: Some(kind), surface_transformation: Mat3x3::identity(),
+                       surface_placement: Mat4x4::identity(), surface_orientation: Quat::identity(), surface_material: Material::default(), surface_shader: None, surface_id: 0, sampling})
+    }
+    /// The surface the old flag + `[f32; 6]` pair drew, see `SurfaceKind::from_flag`.
+    /// Every vertex the old code drew is on it, a hyperboloid is turned so its axis
+    /// is y and only the upper half (hyperbolic angle 0 to 1) is sampled.
+    pub fn from_flag(flag: u8, coefficients: [f32; 6]) -> Result<JiveSurface, String> {
+        let kind = SurfaceKind::from_flag(flag, coefficients)?;
+        if flag != HYPERBOLOID {
+            return JiveSurface::builder(kind).build();
+        }
+        let mut surface = JiveSurface::builder(kind).v_range(0.0, 10.0).samples(90, 10).build()?;
+        // z onto y, the mirror image of the old (x, z, y) swap so the same points come out
+        surface.surface_orientation = Quat::rotation_x(-PI / 2.0);
+        Ok(surface)
+    }
+    /// Wraps any user defined surface so it can be meshed and rendered like the built in ones.
+    pub fn from_parametric<S: ParametricSurface + 'static>(surface: S, u_samples: usize, v_samples: usize) -> JiveSurface {
+        let sampling = Sampling::new(&surface, u_samples.max(1), v_samples.max(1));
+        JiveSurface{geometry: Geometry::Patches(vec![Box::new(surface)]), kind: None, surface_transformation: Mat3x3::identity(),
+                    surface_placement: Mat4x4::identity(), surface_orientation: Quat::identity(), surface_material: Material::default(), surface_shader: None, surface_id: 0, sampling}
+    }
+    /// A surface made of several separate pieces. They are all sampled over the
+    /// domain of the first one, so they should share it.
+    pub fn from_patches(patches: Vec<Box<dyn ParametricSurface>>, u_samples: usize, v_samples: usize) -> Result<JiveSurface, String> {
+        let first = patches.first().ok_or("a surface needs at least one patch")?;
+        let sampling = Sampling::new(first.as_ref(), u_samples, v_samples);
+        sampling.validate()?;
+        Ok(JiveSurface{geometry: Geometry::Patches(patches), kind: None, surface_transformation: Mat3x3::identity(),
+                       surface_placement: Mat4x4::identity(), surface_orientation: Quat::identity(), surface_material: Material::default(), surface_shader: None, surface_id: 0, sampling})
+    }
+    /// A surface that is already triangulated, such as `jiveimplicit::polygonize` output.
+    /// It renders like any other surface but has no parameters to resample.
+    pub fn from_mesh(mesh: Mesh) -> JiveSurface {
+        let sampling = Sampling{ u_range: (0.0, 1.0), v_range: (0.0, 1.0), u_samples: 1, v_samples: 1 };
+        JiveSurface{geometry: Geometry::Triangles(mesh), kind: None, surface_transformation: Mat3x3::identity(),
+                    surface_placement: Mat4x4::identity(), surface_orientation: Quat::identity(), surface_material: Material::default(), surface_shader: None, surface_id: 0, sampling}
+    }
+    /// Builder for a built in shape with its own ranges and resolution.
+    pub fn builder(kind: SurfaceKind) -> JiveSurfaceBuilder {
+        let (u_samples, v_samples) = Self::default_samples(&kind);
+        // the patches come from the kind in build, once it has been checked
+        JiveSurfaceBuilder::new(Vec::new(), Some(kind), Sampling::new(&kind, u_samples, v_samples))
+    }
+    /// Builder for a user defined surface, starting from its whole domain split 32 x 32.
+    pub fn parametric_builder<S: ParametricSurface + 'static>(surface: S) -> JiveSurfaceBuilder {
+        let sampling = Sampling::new(&surface, 32, 32);
+        JiveSurfaceBuilder::new(vec![Box::new(surface)], None, sampling)
+    }
+
+    fn default_samples(kind: &SurfaceKind) -> (usize, usize) {
+        match kind {
+            SurfaceKind::Sphere { .. } => (90, 6),
+            SurfaceKind::Ellipsoid { .. } => (90, 18),
+            SurfaceKind::Hyperboloid { .. } => (90, 20),
+            SurfaceKind::HyperboloidTwoSheets { .. } => (90, 10),
+            SurfaceKind::Paraboloid { .. } => (90, 10),
+            SurfaceKind::HyperbolicParaboloid { .. } => (90, 10),
+            SurfaceKind::Plane { .. } => (10, 10),
+            SurfaceKind::Cone { .. } => (360, 10),
+            SurfaceKind::EllipticCone { .. } => (90, 20),
+            SurfaceKind::Cylinder { .. } => (90, 10),
+            SurfaceKind::Torus { .. } => (72, 36),
+        }
+    }
+
+    /// The built in shape, or None for any other surface.
+    pub fn kind(&self) -> Option<&SurfaceKind> {
+        self.kind.as_ref()
+    }
+    /// The first (usually only) patch of the surface, None when it was made from a mesh.
+    pub fn parametric(&self) -> Option<&dyn ParametricSurface> {
+        self.patches().next()
+    }
+    pub fn patches(&self) -> impl Iterator<Item = &dyn ParametricSurface> {
+        let patches: &[Box<dyn ParametricSurface>] = match &self.geometry {
+            Geometry::Patches(patches) => patches,
+            Geometry::Triangles(_) => &[],
+        };
+        patches.iter().map(|patch| patch.as_ref())
+    }
+    pub fn sampling(&self) -> &Sampling {
+        &self.sampling
+    }
+    /// Changes the sampled ranges / resolution, e.g. to refine a surface while it is on screen.
+    pub fn set_sampling(&mut self, sampling: Sampling) -> Result<(), String> {
+        sampling.validate()?;
+        self.sampling = sampling;
+        Ok(())
+    }
+    pub fn set_samples(&mut self, u_samples: usize, v_samples: usize) -> Result<(), String> {
+        self.set_sampling(Sampling{ u_samples, v_samples, ..self.sampling })
+    }
+    /// Picks the sample counts so grid edges come out roughly `edge_length` long.
+    pub fn set_edge_length(&mut self, edge_length: f32) -> Result<(), String> {
+        let surface = self.parametric().ok_or("a surface made from a mesh can't be resampled")?;
+        self.sampling = self.sampling.with_edge_length(surface, edge_length)?;
+        Ok(())
     }
     pub fn render(&self) -> Result<(), String> {
-        println!("{:?}", self.surface_flag);
-        println!("{:?}", self.surface_coefficients);
+        match (&self.kind, &self.geometry) {
+            (Some(kind), _) => println!("{:?}", kind),
+            (None, Geometry::Patches(patches)) => println!("parametric surface ({} patches), u: {:?} v: {:?}", patches.len(),
+                                                           self.sampling.u_range, self.sampling.v_range),
+            (None, Geometry::Triangles(mesh)) => println!("mesh, {} vertices {} triangles", mesh.vertex_count(), mesh.triangle_count()),
+        }
         Ok(())
     }
     pub fn surface_data(&self) -> Vec<Vec3f> {
-        let mut vertices = Vec::new();
+        if let Geometry::Triangles(mesh) = &self.geometry {
+            return mesh.positions.clone();
+        }
+        self.patches().flat_map(|patch| {
+            sample_points(patch, &self.sampling).points.into_iter().flatten()
+        }).collect()
+    }
+    /// Unit normal at each point of `surface_data`, in the same order.
+    pub fn surface_normals(&self) -> Vec<Vec3f> {
+        if let Geometry::Triangles(mesh) = &self.geometry {
+            return mesh.normals.clone();
+        }
+        self.patches().flat_map(|patch| {
+            sample_grid(patch, &self.sampling).normals.into_iter().flatten()
+        }).collect()
+    }
+    /// Indexed triangle mesh over the same u/v grid as `surface_data`.
+    pub fn mesh(&self) -> Result<Mesh, String> {
+        if let Geometry::Triangles(mesh) = &self.geometry {
+            return Ok(mesh.clone());
+        }
+        let mut mesh = Mesh::new();
+        for patch in self.patches() {
+            mesh.append(Mesh::from_parametric(patch, &self.sampling)?);
+        }
+        Ok(mesh)
+    }
 
-        for v in (self.vmin..self.vmax).step_by(self.vstep) {
-            let v_param = v as f32;
-                
-            for u in (self.umin..self.umax).step_by(self.ustep) {
-                let u_param = u as f32;
-                    
-                let (x,y,z) = Self::solve(self.surface_flag, v_param, u_param, self.surface_coefficients);
-                    vertices.push(Vec3f::from(x,y,z));
-            }
+    /// The samples with their normals and uvs but no triangles, for a grid under 2x2
+    /// that `mesh` can't triangulate. None when the grid is big enough for `mesh`.
+    pub fn point_mesh(&self) -> Option<Mesh> {
+        let grids: Vec<_> = self.patches().map(|patch| sample_grid(patch, &self.sampling)).collect();
+        if grids.iter().all(|grid| grid.v_params.len() >= 2 && grid.u_params.len() >= 2) {
+            return None;
         }
-        return vertices;
+        let mut mesh = Mesh::new();
+        for grid in grids.iter() {
+            mesh.append(Mesh::from_samples(grid));
+        }
+        Some(mesh)
     }
-    pub fn solve(surface_type: u8, v_parameter: f32, u_parameter: f32, surface_coefficients: [f32; 6]) -> (f32, f32, f32) {
-        let scale: f32 = 1.0 / 20.0;
 
-        match surface_type {
-            CONE => {
-                // in this case v = t (y coordinate) and u = theta
-                // so theta (v) needs to be converted to radians
-                // the optional parameters come in as the steepness and height
+    /// Polylines along `u_lines` constant-u and `v_lines` constant-v parameter lines,
+    /// each sampled at the surface's own resolution. A surface made from a mesh has no
+    /// parameter lines, so its triangle edges are returned instead.
+    pub fn isolines(&self, u_lines: usize, v_lines: usize) -> Vec<Vec<Vec3f>> {
+        if let Geometry::Triangles(mesh) = &self.geometry {
+            return mesh.edges().iter().map(|&(a, b)| vec![mesh.positions[a], mesh.positions[b]]).collect();
+        }
+        self.patches().flat_map(|patch| isolines(patch, &self.sampling, u_lines, v_lines)).collect()
+    }
 
-                let height = surface_coefficients[1];
-                let steepness = surface_coefficients[0];
+    pub fn solve(kind: &SurfaceKind, v_parameter: f32, u_parameter: f32) -> (f32, f32, f32) {
+        match *kind {
+            SurfaceKind::Cone { steepness, height } => {
+                // in this case v = t (distance from the axis) and u = theta
+                // so theta (u) needs to be converted to radians
+                // v runs 0..10 so the top rim lands on z = height
                 let scale = height / (200.0 * steepness);
 
                 let theta = Self::d2rad(u_parameter);
                 let x = scale * v_parameter * theta.cos();
                 let y = scale * v_parameter * theta.sin();
-                let z = scale * surface_coefficients[0] * v_parameter;
+                let z = scale * steepness * v_parameter;
                 (x,y,z)
             }
-            PLANE => {
-                let k: f32 = 1.0 / 20.0;
-                //let xy_scale: f32 = 1.0 / 10.0;
-                let a = surface_coefficients[0];
-                let b = surface_coefficients[1];
-                let c = surface_coefficients[2];
-                let d = surface_coefficients[3];
-
-                let mut x = 0.0;
-                let mut y = 0.0;
-                let mut z = 0.0;
-                // if else is a hammer and everything is a nail
-
-                if a == 0.0 && b == 0.0 && c == 0.0 {
-                    println!("all zero, problem");
-                } else if a != 0.0 && b != 0.0 && c != 0.0 {
-                    // ax + by + cz = d
-                    x = u_parameter; y = v_parameter; z = (1.0 / c) * (d - a*u_parameter - b*v_parameter);
-                } else if a == 0.0 || b == 0.0 || c == 0.0 {
-                    if a == 0.0 && b == 0.0 {
-                        // cz = d
-                        x = u_parameter; y = v_parameter; z = d;
-                    } else if b == 0.0 && c == 0.0 {
-                        // ax = d
-                        x = u_parameter; y = d; z = v_parameter;
-                    } else if a == 0.0 && c == 0.0 {
-                        // by = d
-                        x = d; y = v_parameter; z = u_parameter;
-                    } else {
-                        // ax + by + cz = d
-                        x = u_parameter; y = v_parameter; z = (1.0 / c) * (d - a*u_parameter - b*v_parameter);
+            SurfaceKind::Plane { .. } => {
+                // center + u e1 + v e2, u and v run over -extent..extent
+                // a degenerate plane has no points (the constructors reject it)
+                match kind.plane_basis() {
+                    Ok((center, e1, e2)) => {
+                        let point = |k: usize| SCALE * (center.e[k] + u_parameter * e1.e[k] + v_parameter * e2.e[k]);
+                        (point(0), point(1), point(2))
                     }
+                    Err(_) => (f32::NAN, f32::NAN, f32::NAN),
                 }
-                x *= k; y *= k; z *= k;
-                (x,y,z)
             }
-            ELLIPSOID => {
+            SurfaceKind::Ellipsoid { a, b, c } => {
                 // u = psi and v = theta
                 //
                 // both angles so need to go to radians
                 let theta = Self::d2rad(v_parameter);
                 let psi = Self::d2rad(u_parameter);
-                let a: f32 = surface_coefficients[0] / 2.0;
-                let b: f32 = surface_coefficients[2] / 2.0;
-                let c: f32;
-                let d = ((surface_coefficients[5]).abs()).sqrt() / 2.0;
-
-                if a > b { 
-                    c = b 
-                } else {
-                    c = a
-                }
-                let x = a * d * scale * psi.cos() * theta.sin();
-                let y = b * d * scale * psi.sin() * theta.sin();
-                let z = c * d * scale * theta.cos();
+
+                let x = a * SCALE * psi.cos() * theta.sin();
+                let y = b * SCALE * psi.sin() * theta.sin();
+                let z = c * SCALE * theta.cos();
                 (x,y,z)
 
             }
-            HYPERBOLOID => {
+            SurfaceKind::Hyperboloid { a, b, c } => {
                 let theta = Self::d2rad(u_parameter);
-                let v: f32 = v_parameter as f32 / 10.0;
-                //let scale: f32 = 0.1;
-                let a: f32 = surface_coefficients[0];
-                let b: f32 = surface_coefficients[2];
+                let v: f32 = v_parameter / 10.0;
 
-                let x = scale * a * scale * v.cosh() * theta.cos();
-                let y = scale * b * scale * v.cosh() * theta.sin();
-                let z = scale * v.sinh();
-                (x,z,y)
+                let x = SCALE * a * v.cosh() * theta.cos();
+                let y = SCALE * b * v.cosh() * theta.sin();
+                let z = SCALE * c * v.sinh();
+                (x,y,z)
             }
-            PARABOLOID => {
+            SurfaceKind::HyperboloidTwoSheets { a, b, c } => {
+                // the upper sheet, the lower one is its mirror image
+                let theta = Self::d2rad(u_parameter);
+                let v: f32 = v_parameter / 10.0;
+
+                let x = SCALE * a * v.sinh() * theta.cos();
+                let y = SCALE * b * v.sinh() * theta.sin();
+                let z = SCALE * c * v.cosh();
+                (x,y,z)
+            }
+            SurfaceKind::HyperbolicParaboloid { a, b } => {
+                // polar like the paraboloid: x^2/a^2 - y^2/b^2 = v^2 (cos^2 - sin^2)
+                let theta = Self::d2rad(u_parameter);
+                let v: f32 = v_parameter;
+
+                let x = a * SCALE * v * theta.cos();
+                let y = b * SCALE * v * theta.sin();
+                let z = SCALE * v * v * (2.0 * theta).cos();
+                (x,y,z)
+            }
+            SurfaceKind::EllipticCone { a, b, .. } => {
+                // v = z, negative v is the lower nappe
+                let theta = Self::d2rad(u_parameter);
+                let x = a * SCALE * v_parameter * theta.cos();
+                let y = b * SCALE * v_parameter * theta.sin();
+                let z = SCALE * v_parameter;
+                (x,y,z)
+            }
+            SurfaceKind::Cylinder { a, b, .. } => {
+                let theta = Self::d2rad(u_parameter);
+                let x = a * SCALE * theta.cos();
+                let y = b * SCALE * theta.sin();
+                let z = SCALE * v_parameter;
+                (x,y,z)
+            }
+            SurfaceKind::Torus { major_radius, minor_radius } => {
+                // u goes around the ring, v around the tube
+                let theta = Self::d2rad(u_parameter);
+                let phi = Self::d2rad(v_parameter);
+                let ring = major_radius + minor_radius * phi.cos();
+
+                let x = SCALE * ring * theta.cos();
+                let y = SCALE * ring * theta.sin();
+                let z = SCALE * minor_radius * phi.sin();
+                (x,y,z)
+            }
+            SurfaceKind::Paraboloid { a, b } => {
                 let theta = Self::d2rad(u_parameter);
                 let v: f32 = v_parameter;
-                //let scale: f32 = 0.2;
-                let a: f32 = surface_coefficients[0];
-                let b: f32 = surface_coefficients[2];
 
-                let x = a * scale * v * theta.cos();
-                let y = b * scale * v * theta.sin();
-                let z = scale * v * v;
+                let x = a * SCALE * v * theta.cos();
+                let y = b * SCALE * v * theta.sin();
+                let z = SCALE * v * v;
                 (x,y,z)
             }
-            SPHERE => {
+            SurfaceKind::Sphere { radius } => {
                 // u = psi and v = theta
                 // both angles so need to go to radians
                 let theta = Self::d2rad(v_parameter);
                 let psi = Self::d2rad(u_parameter);
-                let d = ((surface_coefficients[5]).abs()).sqrt() / 2.0; 
 
-                let x = d * scale * psi.cos() * theta.sin();
-                let y = d * scale * psi.sin() * theta.sin();
-                let z = d * scale * theta.cos();
-                (x,y,z)
-            }
-            _ => {
-                // have a nice cone
-                let theta = Self::d2rad(v_parameter);
-                let x = u_parameter * theta.cos();
-                let y = u_parameter * theta.sin();
-                let z = u_parameter;
+                let x = radius * SCALE * psi.cos() * theta.sin();
+                let y = radius * SCALE * psi.sin() * theta.sin();
+                let z = radius * SCALE * theta.cos();
                 (x,y,z)
             }
         }
     }
 
+    // the rotate_ methods turn the surface about its own axes by theta radians, the
+    // orientation is renormalized every time so it never drifts off a pure rotation
     pub fn rotate_roll(&mut self, theta: f32) {
-        let roll_mat = Mat3x3::roll(theta);
-        self.surface_transformation *= &roll_mat;
+        self.rotate(Quat::rotation_x(theta));
+    }
+    pub fn rotate_pitch(&mut self, theta: f32) {
+        self.rotate(Quat::rotation_y(theta));
+    }
+    pub fn rotate_yaw(&mut self, theta: f32) {
+        self.rotate(Quat::rotation_z(theta));
+    }
+    pub fn rotate_about(&mut self, axis: &Vec3f, theta: f32) -> Result<(), String> {
+        self.rotate(Quat::from_axis_angle(axis, theta)?);
+        Ok(())
+    }
+    fn rotate(&mut self, turn: Quat) {
+        self.surface_orientation = (self.surface_orientation * turn).normalize();
+    }
+    /// The orientation followed by surface_transformation, what the vertices go through
+    /// before the placement.
+    pub fn transformation(&self) -> Mat3x3 {
+        self.surface_transformation * self.surface_orientation.to_mat3x3()
+    }
+    pub fn set_shader<S: Shader + 'static>(&mut self, shader: S) {
+        self.surface_shader = Some(Box::new(shader));
+    }
+    /// Moves the surface by `offset` in render units (the built in shapes span about -0.5,0.5).
+    pub fn translate(&mut self, offset: Vec3f) {
+        self.surface_placement *= &Mat4x4::translation(&offset);
     }
 
 
     fn d2rad(degrees: f32) -> f32 {
-        return degrees * (PI / 180.0);
+        degrees * (PI / 180.0)
     }
     
     
 }
 
+// ----- JiveSurfaceBuilder -----
 
+/// Sets the u/v ranges and the resolution before a `JiveSurface` is made.
+/// Ranges default to the whole domain of the surface.
+///
+/// ```ignore
+/// let bowl = JiveSurface::builder(SurfaceKind::paraboloid(1.0, 1.0)?)
+///     .v_range(0.0, 2.5)
+///     .samples(720, 40)
+///     .build()?;
+/// ```
+pub struct JiveSurfaceBuilder {
+    patches: Vec<Box<dyn ParametricSurface>>,
+    kind: Option<SurfaceKind>,
+    sampling: Sampling,
+    edge_length: Option<f32>,
+    material: Material,
+    shader: Option<Box<dyn Shader>>,
+    surface_id: usize,
+}
 
+impl JiveSurfaceBuilder {
+    fn new(patches: Vec<Box<dyn ParametricSurface>>, kind: Option<SurfaceKind>, sampling: Sampling) -> JiveSurfaceBuilder {
+        JiveSurfaceBuilder{ patches, kind, sampling, edge_length: None, material: Material::default(), shader: None, surface_id: 0 }
+    }
+    pub fn u_range(mut self, min: f32, max: f32) -> JiveSurfaceBuilder {
+        self.sampling.u_range = (min, max);
+        self
+    }
+    pub fn v_range(mut self, min: f32, max: f32) -> JiveSurfaceBuilder {
+        self.sampling.v_range = (min, max);
+        self
+    }
+    /// Intervals across the u and v ranges.
+    pub fn samples(mut self, u_samples: usize, v_samples: usize) -> JiveSurfaceBuilder {
+        self.sampling.u_samples = u_samples;
+        self.sampling.v_samples = v_samples;
+        self.edge_length = None;
+        self
+    }
+    /// Derive the sample counts from a target distance between neighbouring samples instead.
+    pub fn edge_length(mut self, edge_length: f32) -> JiveSurfaceBuilder {
+        self.edge_length = Some(edge_length);
+        self
+    }
+    pub fn material(mut self, material: Material) -> JiveSurfaceBuilder {
+        self.material = material;
+        self
+    }
+    pub fn shader<S: Shader + 'static>(mut self, shader: S) -> JiveSurfaceBuilder {
+        self.shader = Some(Box::new(shader));
+        self
+    }
+    pub fn surface_id(mut self, surface_id: usize) -> JiveSurfaceBuilder {
+        self.surface_id = surface_id;
+        self
+    }
+    pub fn build(self) -> Result<JiveSurface, String> {
+        let patches = match self.kind {
+            Some(kind) => kind_patches(kind)?,
+            None => self.patches,
+        };
+        let sampling = match self.edge_length {
+            Some(edge_length) => self.sampling.with_edge_length(patches[0].as_ref(), edge_length)?,
+            None => self.sampling,
+        };
+        sampling.validate()?;
+        Ok(JiveSurface{geometry: Geometry::Patches(patches), kind: self.kind, surface_transformation: Mat3x3::identity(),
+                       surface_placement: Mat4x4::identity(), surface_orientation: Quat::identity(), surface_material: self.material, surface_shader: self.shader, surface_id: self.surface_id, sampling})
+    }
+}
@@ -1 +1,3 @@
-pub mod linear_algebra;
\ No newline at end of file
+pub mod linear_algebra;
+pub mod matrix;
+pub mod quaternion;
@@ -1,22 +1,19 @@
 
 // vec3f
+// vec4f
 // mat3x3
-// mat4x4 (for projection)
+// mat4x4 (placement, view and projection)
 
 // ----- vec3f -----
 
 /*
-Want:
-dot
-cross
-normalize
-
-operator overloads
-new(a,b,c)
-from([a,b,c])
+one vector type for the whole crate: points, directions, normals
+the operators work on values and references alike, so a + b, &a + &b and a * 2.0 all do
+what they look like. Index with v[0] or go straight to v.e
 */
-use std::ops::MulAssign;
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub struct Vec3f{
     pub e : [f32; 3],
 }
@@ -27,53 +24,286 @@ impl Vec3f{
     pub fn from(a: f32, b: f32, c: f32) -> Vec3f {
         Vec3f{e: [a,b,c]}
     }
+    pub fn zero() -> Vec3f {
+        Vec3f{e: [0.0; 3]}
+    }
+    pub fn x(&self) -> f32 {
+        self.e[0]
+    }
+    pub fn y(&self) -> f32 {
+        self.e[1]
+    }
+    pub fn z(&self) -> f32 {
+        self.e[2]
+    }
+    pub fn dot(&self, v: &Vec3f) -> f32 {
+        self.e[0] * v.e[0] + self.e[1] * v.e[1] + self.e[2] * v.e[2]
+    }
+    pub fn cross(&self, v: &Vec3f) -> Vec3f {
+        Vec3f::from(self.e[1]*v.e[2] - self.e[2]*v.e[1],
+                    self.e[2]*v.e[0] - self.e[0]*v.e[2],
+                    self.e[0]*v.e[1] - self.e[1]*v.e[0])
+    }
+    pub fn length_squared(&self) -> f32 {
+        self.dot(self)
+    }
+    pub fn length(&self) -> f32 {
+        self.length_squared().sqrt()
+    }
+    /// Same as `length`.
+    pub fn magnitude(&self) -> f32 {
+        self.length()
+    }
     pub fn normalize(&self) -> Vec3f {
         // returns another vector, doesnt mutate self
+        // a zero vector comes back as NaNs, use try_normalize when that can happen
         let mag = self.magnitude();
         Vec3f{e: [self.e[0] / mag, self.e[1] / mag, self.e[2] / mag]}
     }
-    pub fn magnitude(&self) -> f32 {
-        let mag_squared: f32 = self.e[0] * self.e[0] + self.e[1] * self.e[1] + self.e[2] * self.e[2];
-        return mag_squared.sqrt();
+    /// The unit vector along self, None for a zero (or not finite) vector.
+    pub fn try_normalize(&self) -> Option<Vec3f> {
+        let mag = self.magnitude();
+        if mag > 0.0 && mag.is_finite() {
+            Some(*self / mag)
+        } else {
+            None
+        }
+    }
+    /// self at t = 0, v at t = 1.
+    pub fn lerp(&self, v: &Vec3f, t: f32) -> Vec3f {
+        *self + (*v - *self) * t
+    }
+    pub fn distance(&self, v: &Vec3f) -> f32 {
+        (*v - *self).length()
+    }
+    /// Component-wise minimum.
+    pub fn min(&self, v: &Vec3f) -> Vec3f {
+        Vec3f::from(self.e[0].min(v.e[0]), self.e[1].min(v.e[1]), self.e[2].min(v.e[2]))
+    }
+    /// Component-wise maximum.
+    pub fn max(&self, v: &Vec3f) -> Vec3f {
+        Vec3f::from(self.e[0].max(v.e[0]), self.e[1].max(v.e[1]), self.e[2].max(v.e[2]))
+    }
+}
+
+pub fn vec3f_dot(vec_a: &Vec3f, vec_b: &Vec3f) -> f32 {
+    vec_a.dot(vec_b)
+}
+
+pub fn vec3f_cross(vec_a: &Vec3f, vec_b: &Vec3f) -> Vec3f {
+    vec_a.cross(vec_b)
+}
+
+// ----- Vec3f operators -----
+
+impl Add for Vec3f {
+    type Output = Vec3f;
+    fn add(self, v: Vec3f) -> Vec3f {
+        Vec3f::from(self.e[0] + v.e[0], self.e[1] + v.e[1], self.e[2] + v.e[2])
+    }
+}
+impl Sub for Vec3f {
+    type Output = Vec3f;
+    fn sub(self, v: Vec3f) -> Vec3f {
+        Vec3f::from(self.e[0] - v.e[0], self.e[1] - v.e[1], self.e[2] - v.e[2])
+    }
+}
+impl Mul<f32> for Vec3f {
+    type Output = Vec3f;
+    fn mul(self, scalar: f32) -> Vec3f {
+        Vec3f::from(self.e[0] * scalar, self.e[1] * scalar, self.e[2] * scalar)
+    }
+}
+impl Mul<Vec3f> for f32 {
+    type Output = Vec3f;
+    fn mul(self, v: Vec3f) -> Vec3f {
+        v * self
+    }
+}
+impl Div<f32> for Vec3f {
+    type Output = Vec3f;
+    fn div(self, scalar: f32) -> Vec3f {
+        Vec3f::from(self.e[0] / scalar, self.e[1] / scalar, self.e[2] / scalar)
+    }
+}
+impl Neg for Vec3f {
+    type Output = Vec3f;
+    fn neg(self) -> Vec3f {
+        Vec3f::from(-self.e[0], -self.e[1], -self.e[2])
+    }
+}
+impl Neg for &Vec3f {
+    type Output = Vec3f;
+    fn neg(self) -> Vec3f {
+        -*self
     }
 }
 
-pub fn vec3f_dot(vec_a: Vec3f, vec_b: Vec3f) -> f32 {
-    return vec_a.e[0] * vec_b.e[0] + vec_a.e[1] * vec_b.e[1] + vec_a.e[2] * vec_b.e[2];
+// the by reference versions just copy, a Vec3f is 12 bytes
+macro_rules! vec3f_by_reference {
+    ($op:ident, $method:ident, $rhs:ty) => {
+        impl $op<$rhs> for &Vec3f {
+            type Output = Vec3f;
+            fn $method(self, rhs: $rhs) -> Vec3f {
+                (*self).$method(rhs)
+            }
+        }
+        impl $op<&$rhs> for Vec3f {
+            type Output = Vec3f;
+            fn $method(self, rhs: &$rhs) -> Vec3f {
+                self.$method(*rhs)
+            }
+        }
+        impl $op<&$rhs> for &Vec3f {
+            type Output = Vec3f;
+            fn $method(self, rhs: &$rhs) -> Vec3f {
+                (*self).$method(*rhs)
+            }
+        }
+    };
 }
+vec3f_by_reference!(Add, add, Vec3f);
+vec3f_by_reference!(Sub, sub, Vec3f);
+vec3f_by_reference!(Mul, mul, f32);
+vec3f_by_reference!(Div, div, f32);
 
-pub fn vec3f_cross(vec_a: Vec3f, vec_b: Vec3f) -> Vec3f {
-    let nx = vec_a.e[1]*vec_b.e[2] - vec_a.e[2]*vec_b.e[1];
-    let ny = vec_a.e[2]*vec_b.e[0] - vec_a.e[0]*vec_b.e[2];
-    let nz = vec_a.e[0]*vec_b.e[1] - vec_a.e[1]*vec_b.e[0];
-    return Vec3f::from(nx, ny, nz);
+impl Mul<&Vec3f> for f32 {
+    type Output = Vec3f;
+    fn mul(self, v: &Vec3f) -> Vec3f {
+        *v * self
+    }
 }
 
+impl AddAssign for Vec3f {
+    fn add_assign(&mut self, v: Vec3f) {
+        *self = *self + v;
+    }
+}
+impl AddAssign<&Vec3f> for Vec3f {
+    fn add_assign(&mut self, v: &Vec3f) {
+        *self = *self + *v;
+    }
+}
+impl SubAssign for Vec3f {
+    fn sub_assign(&mut self, v: Vec3f) {
+        *self = *self - v;
+    }
+}
+impl SubAssign<&Vec3f> for Vec3f {
+    fn sub_assign(&mut self, v: &Vec3f) {
+        *self = *self - *v;
+    }
+}
+impl MulAssign<f32> for Vec3f {
+    fn mul_assign(&mut self, scalar: f32) {
+        *self = *self * scalar;
+    }
+}
+impl DivAssign<f32> for Vec3f {
+    fn div_assign(&mut self, scalar: f32) {
+        *self = *self / scalar;
+    }
+}
+
+impl Index<usize> for Vec3f {
+    type Output = f32;
+    fn index(&self, i: usize) -> &f32 {
+        &self.e[i]
+    }
+}
+impl IndexMut<usize> for Vec3f {
+    fn index_mut(&mut self, i: usize) -> &mut f32 {
+        &mut self.e[i]
+    }
+}
+
+// v = M v, M row major
 impl MulAssign<&Mat3x3> for Vec3f {
     fn mul_assign(&mut self, mat: &Mat3x3){
-        self.e[0] = mat.e[0]*self.e[0] + mat.e[1]*self.e[1] + mat.e[2]*self.e[2];
-        self.e[1] = mat.e[3]*self.e[0] + mat.e[4]*self.e[1] + mat.e[5]*self.e[2];
-        self.e[2] = mat.e[6]*self.e[0] + mat.e[7]*self.e[1] + mat.e[8]*self.e[2];
+        let [x, y, z] = self.e;
+        self.e[0] = mat.e[0]*x + mat.e[1]*y + mat.e[2]*z;
+        self.e[1] = mat.e[3]*x + mat.e[4]*y + mat.e[5]*z;
+        self.e[2] = mat.e[6]*x + mat.e[7]*y + mat.e[8]*z;
     }
 }
 
-impl MulAssign<&Mat4x4> for Vec3f {
-    fn mul_assign(&mut self, matrix: &Mat4x4){
-    
-        let w: f32;
-        self.e[0] = self.e[0]*matrix.e[0] + self.e[1]*matrix.e[4] + self.e[2]*matrix.e[8] + matrix.e[12];
-        self.e[1] = self.e[0]*matrix.e[1] + self.e[1]*matrix.e[5] + self.e[2]*matrix.e[9] + matrix.e[13];
-        self.e[2] = self.e[0]*matrix.e[2] + self.e[1]*matrix.e[6] + self.e[2]*matrix.e[10] + matrix.e[14];
-        w = self.e[0]*matrix.e[3] + self.e[1]*matrix.e[7] + self.e[2]*matrix.e[11] + matrix.e[15];
-        if w != 0.0{
-            self.e[0] /= w; self.e[1] /= w; self.e[2] /= w;
+// no Vec3f *= &Mat4x4: it would have to drop w, or divide by it without saying the point
+// went behind the camera. Points go through Vec4f::point and perspective_divide
+
+
+// ----- Vec4f -----
+
+/*
+homogeneous coordinates, a row vector times a Mat4x4 like the projection matrix expects:
+x' = x e[0] + y e[4] + z e[8] + w e[12] and so on, w' picks up the e[3], e[7], e[11], e[15] column
+*/
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct Vec4f{
+    pub e: [f32; 4],
+}
+impl Vec4f{
+    pub fn new(data: [f32; 4]) -> Vec4f {
+        Vec4f{ e: data }
+    }
+    pub fn from(a: f32, b: f32, c: f32, d: f32) -> Vec4f {
+        Vec4f{ e: [a, b, c, d] }
+    }
+    /// w = 1, moved by translations
+    pub fn point(v: &Vec3f) -> Vec4f {
+        Vec4f{ e: [v.e[0], v.e[1], v.e[2], 1.0] }
+    }
+    /// w = 0, only turned and scaled
+    pub fn direction(v: &Vec3f) -> Vec4f {
+        Vec4f{ e: [v.e[0], v.e[1], v.e[2], 0.0] }
+    }
+    pub fn w(&self) -> f32 {
+        self.e[3]
+    }
+    /// x, y, z as they are, no divide
+    pub fn xyz(&self) -> Vec3f {
+        Vec3f::from(self.e[0], self.e[1], self.e[2])
+    }
+    /// x, y, z over w. A w at or below zero is a point level with or behind the camera,
+    /// dividing would flip it onto the screen so it is an error instead.
+    ///
+    /// This is the only way from a Mat4x4 back to a Vec3f, a Vec3f can't be multiplied
+    /// by one and lose the divide:
+    ///
+    /// ```compile_fail
+    /// use jive::la::linear_algebra::{Mat4x4, Vec3f};
+    /// let mut p = Vec3f::from(0.5, -0.25, 2.0);
+    /// p *= &Mat4x4::identity();
+    /// ```
+    pub fn perspective_divide(&self) -> Result<Vec3f, String> {
+        let w = self.e[3];
+        if !(w > 0.0 && w.is_finite()) {
+            return Err(format!("point {:?} is not in front of the camera, w = {w}", self.e));
+        }
+        Ok(Vec3f::from(self.e[0] / w, self.e[1] / w, self.e[2] / w))
+    }
+}
+
+impl Mul<&Mat4x4> for Vec4f {
+    type Output = Vec4f;
+    fn mul(self, matrix: &Mat4x4) -> Vec4f {
+        let mut out = Vec4f::default();
+        for (j, value) in out.e.iter_mut().enumerate() {
+            *value = (0..4).map(|i| self.e[i] * matrix.e[i * 4 + j]).sum();
         }
+        out
+    }
+}
+impl MulAssign<&Mat4x4> for Vec4f {
+    fn mul_assign(&mut self, matrix: &Mat4x4){
+        *self = *self * matrix;
     }
 }
 
 
 // ----- Mat3x3 -----
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Mat3x3{
     pub e: [f32; 9],
 }
@@ -91,14 +321,95 @@ impl Mat3x3{
         let mut dat: [f32; 9] = [0.0; 9];
         dat[0] = 1.0;
         dat[4] = theta.cos();
-        dat[5] = -1.0 * theta.sin();
+        dat[5] = -theta.sin();
         dat[7] = theta.sin();
         dat[8] = theta.cos();
         Mat3x3{ e : dat }
     }
+
+    pub fn transpose(&self) -> Mat3x3 {
+        let m = &self.e;
+        Mat3x3{ e: [m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8]] }
+    }
+    pub fn determinant(&self) -> f32 {
+        let m = &self.e;
+        m[0] * (m[4] * m[8] - m[5] * m[7]) - m[1] * (m[3] * m[8] - m[5] * m[6]) + m[2] * (m[3] * m[7] - m[4] * m[6])
+    }
+    /// The transposed cofactors, self * adjugate = determinant * identity.
+    pub fn adjugate(&self) -> Mat3x3 {
+        let m = &self.e;
+        Mat3x3{ e: [
+            m[4] * m[8] - m[5] * m[7], m[2] * m[7] - m[1] * m[8], m[1] * m[5] - m[2] * m[4],
+            m[5] * m[6] - m[3] * m[8], m[0] * m[8] - m[2] * m[6], m[2] * m[3] - m[0] * m[5],
+            m[3] * m[7] - m[4] * m[6], m[1] * m[6] - m[0] * m[7], m[0] * m[4] - m[1] * m[3],
+        ] }
+    }
+    /// None when the matrix squashes space flat (or is not finite). The inverse transpose
+    /// is what carries normals through a transformation that is not a pure rotation.
+    pub fn inverse(&self) -> Option<Mat3x3> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inverse = Mat3x3{ e: self.adjugate().e.map(|a| a / det) };
+        inverse.e.iter().all(|a| a.is_finite()).then_some(inverse)
+    }
+    /// Eigen-decomposition of a symmetric matrix by cyclic Jacobi rotations.
+    /// Returns the eigenvalues and a rotation whose columns are the matching unit eigenvectors,
+    /// so self = V * diag(eigenvalues) * V^T. Only the upper triangle is read.
+    pub fn symmetric_eigen(&self) -> ([f32; 3], Mat3x3) {
+        let mut a: [f32; 9] = self.e;
+        a[3] = a[1]; a[6] = a[2]; a[7] = a[5];
+        let mut v = Mat3x3::identity();
+
+        for _sweep in 0..JACOBI_SWEEPS {
+            let off = a[1]*a[1] + a[2]*a[2] + a[5]*a[5];
+            let diag = a[0]*a[0] + a[4]*a[4] + a[8]*a[8];
+            if off <= f32::EPSILON * f32::EPSILON * diag || off == 0.0 {
+                break;
+            }
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                let apq = a[p * 3 + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                // rotation angle that zeroes a[p][q]
+                let theta = (a[q * 3 + q] - a[p * 3 + p]) / (2.0 * apq);
+                let t = if theta.abs() > 1.0e18 {
+                    0.5 / theta
+                } else {
+                    theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt())
+                };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                // a = P^T a P, columns first then rows
+                for k in 0..3 {
+                    let (akp, akq) = (a[k * 3 + p], a[k * 3 + q]);
+                    a[k * 3 + p] = c * akp - s * akq;
+                    a[k * 3 + q] = s * akp + c * akq;
+                }
+                for k in 0..3 {
+                    let (apk, aqk) = (a[p * 3 + k], a[q * 3 + k]);
+                    a[p * 3 + k] = c * apk - s * aqk;
+                    a[q * 3 + k] = s * apk + c * aqk;
+                }
+                for k in 0..3 {
+                    let (vkp, vkq) = (v.e[k * 3 + p], v.e[k * 3 + q]);
+                    v.e[k * 3 + p] = c * vkp - s * vkq;
+                    v.e[k * 3 + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+        ([a[0], a[4], a[8]], v)
+    }
 }
-impl MulAssign<&Mat3x3> for Mat3x3 {
-    fn mul_assign(&mut self, mat: &Mat3x3) {
+
+// more than enough for a 3x3, convergence is quadratic
+const JACOBI_SWEEPS: usize = 32;
+impl Mul for Mat3x3 {
+    type Output = Mat3x3;
+    fn mul(self, mat: Mat3x3) -> Mat3x3 {
         let mut dat: [f32; 9] = [0.0; 9];
         for i in 0..3 {
             for j in 0..3 {
@@ -107,12 +418,36 @@ impl MulAssign<&Mat3x3> for Mat3x3 {
                 }
             }
         }
-        self.e = dat;
-
+        Mat3x3{ e: dat }
+    }
+}
+impl MulAssign<&Mat3x3> for Mat3x3 {
+    fn mul_assign(&mut self, mat: &Mat3x3) {
+        *self = *self * *mat;
+    }
+}
+// M v, the same as v *= &M
+impl Mul<Vec3f> for Mat3x3 {
+    type Output = Vec3f;
+    fn mul(self, v: Vec3f) -> Vec3f {
+        let mut out = v;
+        out *= &self;
+        out
     }
 }
 
 // ---- 4x4 Matrices ----
+
+/*
+row vector convention like the projection: a point is (x, y, z, 1) times the matrix, the
+translation sits in e[12], e[13], e[14] and a * b applies a first and then b. So a surface
+goes through placement * view * projection in that order
+
+    ----- look_at -----
+    the camera looks down its own +z with +y up and +x to the right, the same way round as
+    the projection which takes w = z
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Mat4x4{
     pub e: [f32; 16],
 }
@@ -121,6 +456,11 @@ impl Mat4x4 {
         let dat: [f32; 16] = [0.0; 16];
         Mat4x4{ e : dat }
     }
+    pub fn identity() -> Mat4x4 {
+        let mut dat: [f32; 16] = [0.0; 16];
+        dat[0] = 1.0; dat[5] = 1.0; dat[10] = 1.0; dat[15] = 1.0;
+        Mat4x4{ e : dat }
+    }
     pub fn projection(&mut self, h: &f32, w: &f32, fov: &f32, zfar: &f32, znear: &f32){
         let a: f32 = h / w;
         let f: f32 = 1.0 / (fov*0.5).tan();
@@ -132,9 +472,150 @@ impl Mat4x4 {
         self.e[11] = 1.0;
         self.e[14] = (-1.0 * zfar * znear) / (zfar - znear);
     }
-}
 
+    /// Moves points by `offset`, directions are left alone.
+    pub fn translation(offset: &Vec3f) -> Mat4x4 {
+        let mut mat = Mat4x4::identity();
+        mat.e[12] = offset.e[0]; mat.e[13] = offset.e[1]; mat.e[14] = offset.e[2];
+        mat
+    }
+    /// Scales each axis by its own factor.
+    pub fn scale(factors: &Vec3f) -> Mat4x4 {
+        let mut mat = Mat4x4::identity();
+        mat.e[0] = factors.e[0]; mat.e[5] = factors.e[1]; mat.e[10] = factors.e[2];
+        mat
+    }
+    /// Turns by `theta` radians about `axis`, counterclockwise looking down the axis.
+    pub fn rotation(axis: &Vec3f, theta: f32) -> Result<Mat4x4, String> {
+        let [x, y, z] = axis.try_normalize().ok_or(format!("rotation axis must be non zero and finite, got {:?}", axis.e))?.e;
+        let (sin, cos) = theta.sin_cos();
+        let t = 1.0 - cos;
+        // the usual column vector rotation matrix, transposed for row vectors
+        let mut mat = Mat4x4::identity();
+        mat.e[0] = t * x * x + cos;     mat.e[4] = t * x * y - sin * z; mat.e[8] = t * x * z + sin * y;
+        mat.e[1] = t * x * y + sin * z; mat.e[5] = t * y * y + cos;     mat.e[9] = t * y * z - sin * x;
+        mat.e[2] = t * x * z - sin * y; mat.e[6] = t * y * z + sin * x; mat.e[10] = t * z * z + cos;
+        Ok(mat)
+    }
+    /// The view from `eye` towards `target`: eye goes to the origin and target onto +z.
+    pub fn look_at(eye: &Vec3f, target: &Vec3f, up: &Vec3f) -> Result<Mat4x4, String> {
+        let forward = (target - eye).try_normalize()
+            .ok_or(format!("camera at {:?} cannot look at itself", eye.e))?;
+        let right = up.cross(&forward).try_normalize()
+            .ok_or(format!("up {:?} must not be parallel to the view direction", up.e))?;
+        let camera_up = forward.cross(&right);
 
+        let mut mat = Mat4x4::identity();
+        for (column, axis) in [right, camera_up, forward].iter().enumerate() {
+            for row in 0..3 {
+                mat.e[row * 4 + column] = axis.e[row];
+            }
+            mat.e[12 + column] = -axis.dot(eye);
+        }
+        Ok(mat)
+    }
+    /// Parallel projection of the box between the planes onto x, y in -1,1 and z in 0,1,
+    /// the same ranges the perspective projection uses. w stays 1.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Result<Mat4x4, String> {
+        for (name, low, high) in [("left/right", left, right), ("bottom/top", bottom, top), ("near/far", near, far)] {
+            if !(low.is_finite() && high.is_finite() && low < high) {
+                return Err(format!("orthographic {name} must be finite and increasing, got {low}..{high}"));
+            }
+        }
+        let mut mat = Mat4x4::identity();
+        mat.e[0] = 2.0 / (right - left);
+        mat.e[5] = 2.0 / (top - bottom);
+        mat.e[10] = 1.0 / (far - near);
+        mat.e[12] = -(right + left) / (right - left);
+        mat.e[13] = -(top + bottom) / (top - bottom);
+        mat.e[14] = -near / (far - near);
+        Ok(mat)
+    }
 
+    pub fn transpose(&self) -> Mat4x4 {
+        let mut mat = Mat4x4::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                mat.e[j * 4 + i] = self.e[i * 4 + j];
+            }
+        }
+        mat
+    }
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.minors();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+    /// None when the matrix squashes space flat (or is not finite).
+    pub fn inverse(&self) -> Option<Mat4x4> {
+        let (s, c) = self.minors();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let m = &self.e;
+        let adjugate = [
+             m[5] * c[5] - m[6] * c[4] + m[7] * c[3],
+            -m[1] * c[5] + m[2] * c[4] - m[3] * c[3],
+             m[13] * s[5] - m[14] * s[4] + m[15] * s[3],
+            -m[9] * s[5] + m[10] * s[4] - m[11] * s[3],
+
+            -m[4] * c[5] + m[6] * c[2] - m[7] * c[1],
+             m[0] * c[5] - m[2] * c[2] + m[3] * c[1],
+            -m[12] * s[5] + m[14] * s[2] - m[15] * s[1],
+             m[8] * s[5] - m[10] * s[2] + m[11] * s[1],
 
+             m[4] * c[4] - m[5] * c[2] + m[7] * c[0],
+            -m[0] * c[4] + m[1] * c[2] - m[3] * c[0],
+             m[12] * s[4] - m[13] * s[2] + m[15] * s[0],
+            -m[8] * s[4] + m[9] * s[2] - m[11] * s[0],
+
+            -m[4] * c[3] + m[5] * c[1] - m[6] * c[0],
+             m[0] * c[3] - m[1] * c[1] + m[2] * c[0],
+            -m[12] * s[3] + m[13] * s[1] - m[14] * s[0],
+             m[8] * s[3] - m[9] * s[1] + m[10] * s[0],
+        ];
+        let inverse = Mat4x4{ e: adjugate.map(|a| a / det) };
+        inverse.e.iter().all(|a| a.is_finite()).then_some(inverse)
+    }
+    // the 2x2 minors of the top two rows (s) and the bottom two (c), the determinant and
+    // the adjugate are both built out of them
+    fn minors(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.e;
+        let s = [
+            m[0] * m[5] - m[4] * m[1], m[0] * m[6] - m[4] * m[2], m[0] * m[7] - m[4] * m[3],
+            m[1] * m[6] - m[5] * m[2], m[1] * m[7] - m[5] * m[3], m[2] * m[7] - m[6] * m[3],
+        ];
+        let c = [
+            m[8] * m[13] - m[12] * m[9], m[8] * m[14] - m[12] * m[10], m[8] * m[15] - m[12] * m[11],
+            m[9] * m[14] - m[13] * m[10], m[9] * m[15] - m[13] * m[11], m[10] * m[15] - m[14] * m[11],
+        ];
+        (s, c)
+    }
+}
 
+impl Mul for Mat4x4 {
+    type Output = Mat4x4;
+    fn mul(self, mat: Mat4x4) -> Mat4x4 {
+        let mut dat: [f32; 16] = [0.0; 16];
+        for i in 0..4 {
+            for j in 0..4 {
+                for k in 0..4 {
+                    dat[i * 4 + j] += self.e[i * 4 + k] * mat.e[k * 4 + j];
+                }
+            }
+        }
+        Mat4x4{ e: dat }
+    }
+}
+impl Mul<&Mat4x4> for Mat4x4 {
+    type Output = Mat4x4;
+    fn mul(self, mat: &Mat4x4) -> Mat4x4 {
+        self * *mat
+    }
+}
+// self = self * mat, so mat is applied after what self already does
+impl MulAssign<&Mat4x4> for Mat4x4 {
+    fn mul_assign(&mut self, mat: &Mat4x4) {
+        *self = *self * *mat;
+    }
+}
@@ -7,58 +7,121 @@
 // ---- 2x2 Matrix ----
 // could be seen as unnecessary but with a struct for 2x2, linear algebra goes under the hood
 
-// eigenvectors
-// eigenvalues
+// eigenvalues and eigenvectors, real or complex
+// determinant, trace, inverse
 // multiply operator: (Mat2x2 * Mat2x2) and (scalar * Mat2x2)
 // transpose
+// the 3x3 and 4x4 matrices live in linear_algebra
+
+/*
+    ----- Eigen -----
+    with h = (a - d) / 2 the eigenvalues are (a + d) / 2 +- sqrt(h^2 + bc). The bigger one
+    in size is taken straight from that and the other as det / it, so a small eigenvalue
+    next to a big one does not cancel away to nothing. An eigenvector is perpendicular to
+    the longer row of A - l I, flipped so its larger component is positive
+*/
 use std::ops::{Mul, MulAssign};
-use crate::Vec3f;
 
+// eigenvalues closer than this (relative to the largest entry) count as one
+const REPEATED: f32 = 1e-5;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Mat2x2 {
     pub e: [f32; 4],
 }
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Eigen2x2 {
+    /// two different real eigenvalues, the larger first, each with its unit eigenvector
+    Real { values: [f32; 2], vectors: [[f32; 2]; 2] },
+    /// one eigenvalue twice. When the matrix is value * I every direction is an eigenvector
+    /// and `vector` is just the x axis, otherwise (a shear) `vector` is the only direction
+    Repeated { value: f32, vector: [f32; 2], every_direction: bool },
+    /// re +- i im with im > 0, the map turns every direction so none is an eigenvector
+    Complex { re: f32, im: f32 },
+}
+
 impl Mat2x2 {
     pub fn new(data: [f32; 4]) -> Mat2x2 {
         Mat2x2{ e: data }
     }
+    pub fn identity() -> Mat2x2 {
+        Mat2x2{ e: [1.0, 0.0, 0.0, 1.0] }
+    }
     pub fn to_array(self) -> [f32; 4] {
         [self.e[0], self.e[1], self.e[2], self.e[3]]
     }
-    pub fn eigenvectors(&self, lambda1: f32, lambda2: f32) -> ([f32;2], [f32; 2]) {
-        if self.e[2] != 0.0 {
-            return ([(lambda1 - self.e[3])/self.e[1] , self.e[2]/self.e[1]], [(lambda2 - self.e[3])/self.e[1], self.e[2]/self.e[1]]);
-        } else if self.e[1] != 0.0 {
-            return ([1.0 , (lambda1 - self.e[0])/self.e[1]], [1.0, (lambda2 - self.e[0])/self.e[1]]);
-        } else if self.e[2] == 0.0 && self.e[1] == 0.0 {
-            return ([1.0, 0.0], [0.0, 1.0]);
-        } else {
-            // something is very wrong if this happens
-            // result here
-            return ([0.0, 0.0], [0.0, 0.0]);
-        }
+    pub fn trace(&self) -> f32 {
+        self.e[0] + self.e[3]
     }
-    pub fn eigenvalues(&self) -> (f32, f32) {
-        let characteristic_polynomial = [1.0, -1.0 * (self.e[0] + self.e[3]), (self.e[0]*self.e[3]-self.e[1]*self.e[2])];
-        Self::polynomial_roots(characteristic_polynomial)
+    pub fn determinant(&self) -> f32 {
+        self.e[0] * self.e[3] - self.e[1] * self.e[2]
     }
-    fn polynomial_roots(coefs: [f32; 3]) -> (f32, f32) {
-        // coefs = a,b,c => ax^2 + bx + c
-        let discriminant = coefs[1]*coefs[1] - 4.0 * coefs[0] * coefs[2];
-        let positive_root = ((-1.0 * coefs[1]) + discriminant.sqrt()) / 2.0 * coefs[0];
-        let negative_root = ((-1.0 * coefs[1]) - discriminant.sqrt()) / 2.0 * coefs[0];
-        (positive_root, negative_root)
+    /// None when the matrix squashes the plane onto a line (or is not finite).
+    pub fn inverse(&self) -> Option<Mat2x2> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let [a, b, c, d] = self.e;
+        let inverse = Mat2x2{ e: [d / det, -b / det, -c / det, a / det] };
+        inverse.e.iter().all(|x| x.is_finite()).then_some(inverse)
     }
-    pub fn transpose(&mut self) -> Mat2x2 {
+    pub fn transpose(&self) -> Mat2x2 {
         // a, b, c, d -> a, c, b, d
         Mat2x2{ e: [self.e[0], self.e[2], self.e[1], self.e[3]]}
     }
+    /// M v for the column vector v.
+    pub fn apply(&self, v: [f32; 2]) -> [f32; 2] {
+        [self.e[0] * v[0] + self.e[1] * v[1], self.e[2] * v[0] + self.e[3] * v[1]]
+    }
+
+    pub fn eigen(&self) -> Eigen2x2 {
+        let [a, b, c, d] = self.e;
+        let scale = self.e.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+        let half_trace = (a + d) / 2.0;
+        let h = (a - d) / 2.0;
+        let discriminant = h * h + b * c;
+        let gap = discriminant.abs().sqrt();
+
+        if gap <= REPEATED * scale {
+            let close = |x: f32| x.abs() <= REPEATED * scale;
+            if close(h) && close(b) && close(c) {
+                return Eigen2x2::Repeated{ value: half_trace, vector: [1.0, 0.0], every_direction: true };
+            }
+            return Eigen2x2::Repeated{ value: half_trace, vector: self.eigenvector(half_trace), every_direction: false };
+        }
+        if discriminant < 0.0 {
+            return Eigen2x2::Complex{ re: half_trace, im: gap };
+        }
+
+        // the bigger one in size first, then the other from the determinant
+        let big = half_trace + if half_trace >= 0.0 { gap } else { -gap };
+        let small = if big != 0.0 { self.determinant() / big } else { half_trace - gap };
+        let values = if big >= small { [big, small] } else { [small, big] };
+        Eigen2x2::Real{ values, vectors: [self.eigenvector(values[0]), self.eigenvector(values[1])] }
+    }
+
+    // a unit vector perpendicular to the longer row of A - l I
+    fn eigenvector(&self, lambda: f32) -> [f32; 2] {
+        let rows = [[self.e[0] - lambda, self.e[1]], [self.e[2], self.e[3] - lambda]];
+        let length = |r: &[f32; 2]| (r[0] * r[0] + r[1] * r[1]).sqrt();
+        let row = if length(&rows[0]) >= length(&rows[1]) { rows[0] } else { rows[1] };
+        let size = length(&row);
+        if size == 0.0 {
+            return [1.0, 0.0];
+        }
+        let v = [-row[1] / size, row[0] / size];
+        let flip = if v[0].abs() >= v[1].abs() { v[0] < 0.0 } else { v[1] < 0.0 };
+        if flip { [-v[0], -v[1]] } else { v }
+    }
 }
 
 // override for matrix * matrix
 impl Mul<Mat2x2> for Mat2x2 {
     type Output = Mat2x2;
     fn mul(self, m1: Mat2x2) -> Mat2x2 {
-        Mat2x2{ e: [self.e[0] * m1.e[0] + self.e[1] * m1.e[2], 
+        Mat2x2{ e: [self.e[0] * m1.e[0] + self.e[1] * m1.e[2],
                     self.e[0] * m1.e[1] + self.e[1] * m1.e[3],
                     self.e[2] * m1.e[0] + self.e[3] * m1.e[2],
                     self.e[2] * m1.e[1] + self.e[3] * m1.e[3]] }
@@ -74,77 +137,7 @@ impl Mul<f32> for Mat2x2 {
 
 impl MulAssign<f32> for Mat2x2 {
     fn mul_assign(&mut self, scalar: f32) {
-        self.e[0] *= scalar; self.e[1] *= scalar; 
+        self.e[0] *= scalar; self.e[1] *= scalar;
         self.e[2] *= scalar; self.e[3] *= scalar;
     }
 }
-
-
-// ---- 3x3 Matrices ----
-#[derive(Copy, Clone)]
-pub struct Mat3x3{
-    pub e: [f32; 9],
-}
-impl Mat3x3{
-    pub fn new() -> Mat3x3{
-        let dat: [f32; 9] = [0.0; 9];
-        Mat3x3{ e : dat}
-    }
-    pub fn identity() -> Mat3x3 {
-        let mut dat: [f32; 9] = [0.0; 9];
-        dat[0] = 1.0; dat[4] = 1.0; dat[8] = 1.0;
-        Mat3x3{ e : dat }
-    }
-    pub fn rotation_x(&mut self, theta: f32){
-        self.e[0] = 1.0;
-        self.e[4] = theta.cos();
-        self.e[5] = -1.0 * theta.sin();
-        self.e[7] = theta.sin();
-        self.e[8] = theta.cos();
-    }
-    pub fn rotation_y(&mut self, theta: f32){
-        self.e[0] = theta.cos();
-        self.e[2] = theta.sin();
-        self.e[4] = 1.0;
-        self.e[6] = -1.0 * theta.sin();
-        self.e[8] = theta.cos();
-    }
-    pub fn rotation_z(&mut self, theta: f32){
-        self.e[0] = theta.cos();
-        self.e[1] = -1.0 * theta.sin();
-        self.e[3] = theta.sin();
-        self.e[4] = theta.cos();
-        self.e[8] = 1.0;
-    }
-}
-
-impl Mul<Vec3f> for Mat3x3 {
-    type Output = Vec3f;
-    fn mul(self, v: Vec3f) -> Vec3f {
-        Vec3f::new(&[self.e[0]*v.e[0] + self.e[1]*v.e[1] + self.e[2]*v.e[2],
-                    self.e[3]*v.e[0] + self.e[4]*v.e[1] + self.e[5]*v.e[2],
-                    self.e[6]*v.e[0] + self.e[7]*v.e[1] + self.e[8]*v.e[2]])
-    }
-}
-
-// ---- 4x4 Matrices ----
-pub struct Mat4x4{
-    pub e: [f32; 16],
-}
-impl Mat4x4 {
-    pub fn new() -> Mat4x4{
-        let dat: [f32; 16] = [0.0; 16];
-        Mat4x4{ e : dat }
-    }
-    pub fn projection(&mut self, h: &f32, w: &f32, fov: &f32, zfar: &f32, znear: &f32){
-        let a: f32 = h / w;
-        let f: f32 = 1.0 / (fov*0.5).tan();
-        let q: f32 = zfar / (zfar - znear);
-    
-        self.e[0] = a * f;
-        self.e[5] = f;
-        self.e[10] = q;
-        self.e[11] = 1.0;
-        self.e[14] = (-1.0 * zfar * znear) / (zfar - znear);
-    }
-}
\ No newline at end of file
@@ -0,0 +1,155 @@
+/*
+
+----- Quaternion -----
+
+rotations as unit quaternions w + xi + yj + zk. Multiplying rotation matrices frame after
+frame lets rounding pile up until they shear and scale, a quaternion only needs dividing
+by its length to be a clean rotation again
+
+    ----- Conventions -----
+    angles are radians, counterclockwise looking down the axis like Mat3x3::roll
+    a * b turns by b first and then by a, like Mat3x3 acting on v = M v
+    roll, pitch and yaw turn about x, y and z, in that order
+
+*/
+
+use std::ops::{Mul, MulAssign};
+use crate::la::linear_algebra::{Mat3x3, Mat4x4, Vec3f};
+
+// past this the two are so close that a straight lerp is as good and does not divide by ~0
+const SLERP_LINEAR: f32 = 0.9995;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quat {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quat {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Quat {
+        Quat{ w, x, y, z }
+    }
+    pub fn identity() -> Quat {
+        Quat{ w: 1.0, x: 0.0, y: 0.0, z: 0.0 }
+    }
+    /// Turns by `theta` about `axis`, which does not have to be a unit vector.
+    pub fn from_axis_angle(axis: &Vec3f, theta: f32) -> Result<Quat, String> {
+        let axis = axis.try_normalize()
+            .ok_or(format!("rotation axis must be non zero and finite, got {:?}", axis.e))?;
+        Ok(Quat::about_unit(&axis, theta))
+    }
+    pub fn rotation_x(theta: f32) -> Quat {
+        Quat::about_unit(&Vec3f::from(1.0, 0.0, 0.0), theta)
+    }
+    pub fn rotation_y(theta: f32) -> Quat {
+        Quat::about_unit(&Vec3f::from(0.0, 1.0, 0.0), theta)
+    }
+    pub fn rotation_z(theta: f32) -> Quat {
+        Quat::about_unit(&Vec3f::from(0.0, 0.0, 1.0), theta)
+    }
+    /// Roll about x, then pitch about y, then yaw about z, all about the fixed axes.
+    pub fn from_euler(roll: f32, pitch: f32, yaw: f32) -> Quat {
+        Quat::rotation_z(yaw) * Quat::rotation_y(pitch) * Quat::rotation_x(roll)
+    }
+    fn about_unit(axis: &Vec3f, theta: f32) -> Quat {
+        let (sin, cos) = (theta * 0.5).sin_cos();
+        Quat{ w: cos, x: axis.e[0] * sin, y: axis.e[1] * sin, z: axis.e[2] * sin }
+    }
+
+    pub fn dot(&self, q: &Quat) -> f32 {
+        self.w * q.w + self.x * q.x + self.y * q.y + self.z * q.z
+    }
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+    /// Back to length 1. A zero (or broken) quaternion has no rotation in it and gives
+    /// the identity.
+    pub fn normalize(&self) -> Quat {
+        let length = self.length();
+        if length == 0.0 || !length.is_finite() {
+            return Quat::identity();
+        }
+        Quat{ w: self.w / length, x: self.x / length, y: self.y / length, z: self.z / length }
+    }
+    /// The opposite rotation, for a unit quaternion.
+    pub fn conjugate(&self) -> Quat {
+        Quat{ w: self.w, x: -self.x, y: -self.y, z: -self.z }
+    }
+
+    /// Turns `v` by this rotation.
+    pub fn rotate(&self, v: &Vec3f) -> Vec3f {
+        // v + 2w (u x v) + 2 u x (u x v), u the vector part
+        let u = Vec3f::from(self.x, self.y, self.z);
+        let t = 2.0 * u.cross(v);
+        v + self.w * t + u.cross(&t)
+    }
+
+    /// Part way from this rotation to `q` at a steady angular speed, t from 0 to 1.
+    /// Always goes the short way round.
+    pub fn slerp(&self, q: &Quat, t: f32) -> Quat {
+        let mut d = self.dot(q);
+        // q and -q are the same rotation, pick the one on this side
+        let q = if d < 0.0 {
+            d = -d;
+            Quat{ w: -q.w, x: -q.x, y: -q.y, z: -q.z }
+        } else {
+            *q
+        };
+        let (a, b) = if d > SLERP_LINEAR {
+            (1.0 - t, t)
+        } else {
+            let angle = d.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        Quat{ w: a * self.w + b * q.w, x: a * self.x + b * q.x, y: a * self.y + b * q.y, z: a * self.z + b * q.z }
+            .normalize()
+    }
+
+    /// The rotation as a Mat3x3 acting on columns, v = M v.
+    pub fn to_mat3x3(&self) -> Mat3x3 {
+        let Quat{ w, x, y, z } = self.normalize();
+        Mat3x3{ e: [
+            1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y),
+            2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x),
+            2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y),
+        ] }
+    }
+    /// The rotation as a Mat4x4 for row vectors, ready to go into a placement.
+    pub fn to_mat4x4(&self) -> Mat4x4 {
+        let turn = self.to_mat3x3();
+        let mut mat = Mat4x4::identity();
+        for i in 0..3 {
+            for j in 0..3 {
+                mat.e[j * 4 + i] = turn.e[i * 3 + j];
+            }
+        }
+        mat
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::identity()
+    }
+}
+
+// self * q turns by q first
+impl Mul for Quat {
+    type Output = Quat;
+    fn mul(self, q: Quat) -> Quat {
+        Quat{
+            w: self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z,
+            x: self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y,
+            y: self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x,
+            z: self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w,
+        }
+    }
+}
+impl MulAssign for Quat {
+    fn mul_assign(&mut self, q: Quat) {
+        *self = *self * q;
+    }
+}
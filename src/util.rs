@@ -1,2 +1,9 @@
+pub mod backend;
 pub mod bustin;
-pub mod colors;
\ No newline at end of file
+pub mod depth;
+pub mod font;
+pub mod framebuffer;
+pub mod lighting;
+pub mod raster;
+pub mod shader;
+pub mod svg;
@@ -0,0 +1,195 @@
+/*
+----- Render backend -----
+what the render_ functions draw on: the SDL window, a Framebuffer in memory or an SvgDocument.
+A raster backend only has to put rasterized pixels somewhere, the shapes all come from
+util::raster so the window and a framebuffer get the same pixels. A vector backend
+overrides the shapes and writes them out as they are
+
+    ----- A frame -----
+    clear, draw, present. present shows the frame in a window, the in-memory backends
+    have nothing to do there
+
+    ----- Depth -----
+    the depth_ calls take points straight from project_vertex: x and y in pixels and z the
+    projected depth, 0 at f_near and 1 at f_far. A Framebuffer made with_depth tests every
+    pixel against a z-buffer, everything else draws them in call order unless it is wrapped
+    in a DepthSorted, which puts them in painter's order first
+
+    ----- Blending -----
+    a pixel with coverage 255 and an opaque color is written as is, anything less is
+    blended over what is there the way SDL's BLENDMODE_BLEND does it. The blending is
+    done here in software for every raster backend: the window reads the pixels under
+    the edges back and writes the blended colors opaque, so its renderer (the GPU with
+    the OpenGL one) never gets to round them its own way
+*/
+
+extern crate sdl2;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, WindowCanvas};
+use std::collections::BTreeMap;
+
+use crate::la::linear_algebra::Vec3f;
+use crate::util::raster;
+use crate::util::raster::Clip;
+
+pub trait RenderBackend {
+    /// Width and height in pixels, nothing is rasterized outside them.
+    fn size(&self) -> Result<(u32, u32), String>;
+    /// Draws rasterized (x, y, coverage) pixels in `color`, all of them on the target.
+    fn plot(&mut self, pixels: &[(i32, i32, u8)], color: Color) -> Result<(), String>;
+    /// Fills the whole target with `color`, blending nothing.
+    fn clear(&mut self, color: Color) -> Result<(), String>;
+
+    fn present(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+    fn point(&mut self, x: i32, y: i32, color: Color) -> Result<(), String> {
+        self.fill_rect(x, y, 1, 1, color)
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Color) -> Result<(), String> {
+        self.plot(&rasterize(self.size()?, |clip, plot| raster::fill_rect(clip, x, y, width, height, plot)), color)
+    }
+    /// Antialiased when `thickness` is 1 or less.
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), thickness: u8, color: Color) -> Result<(), String> {
+        self.plot(&line_pixels(self.size()?, from, to, thickness), color)
+    }
+    /// Corners in pixel units, pixel (x, y) covering [x, x + 1) x [y, y + 1). Triangles
+    /// sharing an edge do not both fill the pixels along it.
+    fn filled_triangle(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32), color: Color) -> Result<(), String> {
+        self.plot(&rasterize(self.size()?, |clip, plot| raster::fill_polygon(clip, &[a, b, c], plot)), color)
+    }
+    fn filled_circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        self.plot(&rasterize(self.size()?, |clip, plot| raster::filled_circle(clip, center, radius, plot)), color)
+    }
+    fn circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        self.plot(&rasterize(self.size()?, |clip, plot| raster::circle(clip, center, radius, plot)), color)
+    }
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color) -> Result<(), String> {
+        self.plot(&rasterize(self.size()?, |clip, plot| raster::text(clip, x, y, text, plot)), color)
+    }
+
+    /// A `size` x `size` square with its top left corner at `at`.
+    fn depth_point(&mut self, at: &Vec3f, size: u32, color: Color) -> Result<(), String> {
+        let (x, y) = pixel(at);
+        self.fill_rect(x, y, size, size, color)
+    }
+    fn depth_line(&mut self, from: &Vec3f, to: &Vec3f, thickness: u8, color: Color) -> Result<(), String> {
+        self.line(pixel(from), pixel(to), thickness, color)
+    }
+    fn depth_triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, color: Color) -> Result<(), String> {
+        self.filled_triangle((a.e[0], a.e[1]), (b.e[0], b.e[1]), (c.e[0], c.e[1]), color)
+    }
+    /// A depth triangle colored pixel by pixel: `shade` gets the weights of a, b and c at
+    /// the pixel center, straight across the screen and adding up to 1. A backend that
+    /// can only fill one color uses the color at the middle.
+    fn shaded_triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, shade: &mut dyn FnMut([f32; 3]) -> Color) -> Result<(), String> {
+        let color = shade([1.0 / 3.0; 3]);
+        self.depth_triangle(a, b, c, color)
+    }
+}
+
+// the pixel a projected point lands in, floored so -0.5 is pixel -1 and not 0
+pub(crate) fn pixel(p: &Vec3f) -> (i32, i32) {
+    (p.e[0].floor() as i32, p.e[1].floor() as i32)
+}
+
+pub(crate) fn line_pixels(size: (u32, u32), from: (i32, i32), to: (i32, i32), thickness: u8) -> Vec<(i32, i32, u8)> {
+    if thickness <= 1 {
+        rasterize(size, |clip, plot| raster::aa_line(clip, from, to, plot))
+    } else {
+        rasterize(size, |clip, plot| raster::thick_line(clip, from, to, thickness, plot))
+    }
+}
+
+pub(crate) fn rasterize(size: (u32, u32), draw: impl FnOnce(&Clip, &mut dyn FnMut(i32, i32, u8))) -> Vec<(i32, i32, u8)> {
+    let clip = Clip{ width: size.0, height: size.1 };
+    let mut pixels = Vec::new();
+    draw(&clip, &mut |x, y, coverage| pixels.push((x, y, coverage)));
+    pixels
+}
+
+// the alpha a partly covered pixel is blended with
+pub(crate) fn coverage_alpha(color: Color, coverage: u8) -> u8 {
+    (color.a as u32 * coverage as u32 / 255) as u8
+}
+
+// color over the (r, g, b, a) under it at this alpha, SDL's BLENDMODE_BLEND
+pub(crate) fn blend_over(under: [u8; 4], color: Color, alpha: u8) -> [u8; 4] {
+    if alpha == 255 {
+        return [color.r, color.g, color.b, color.a];
+    }
+    let mul = |a: u8, b: u8| (a as u32 * b as u32 / 255) as u8;
+    let inverse = 255 - alpha;
+    [mul(inverse, under[0]) + mul(color.r, alpha),
+     mul(inverse, under[1]) + mul(color.g, alpha),
+     mul(inverse, under[2]) + mul(color.b, alpha),
+     mul(inverse, under[3]) + alpha]
+}
+
+impl RenderBackend for WindowCanvas {
+    fn size(&self) -> Result<(u32, u32), String> {
+        self.output_size()
+    }
+    fn plot(&mut self, pixels: &[(i32, i32, u8)], color: Color) -> Result<(), String> {
+        let (solid, edge): (Vec<_>, Vec<_>) = pixels.iter()
+            .map(|&(x, y, coverage)| (x, y, coverage_alpha(color, coverage)))
+            .filter(|p| p.2 > 0)
+            .partition(|p| p.2 == 255);
+
+        // everything is written as is, the edges come out of blend_over already blended
+        let previous = self.blend_mode();
+        self.set_blend_mode(BlendMode::None);
+        self.set_draw_color(color);
+        let solid: Vec<Point> = solid.iter().map(|p| Point::new(p.0, p.1)).collect();
+        self.draw_points(&solid[..])?;
+        let blended = blend_edges(self, &edge, color);
+        self.set_blend_mode(previous);
+        blended
+    }
+    fn clear(&mut self, color: Color) -> Result<(), String> {
+        self.set_draw_color(color);
+        WindowCanvas::clear(self);
+        Ok(())
+    }
+    fn present(&mut self) -> Result<(), String> {
+        WindowCanvas::present(self);
+        Ok(())
+    }
+}
+
+// reads back the pixels under the edge in one rectangle, blends them like a Framebuffer
+// would and draws the results, one batch per color
+fn blend_edges(canvas: &mut WindowCanvas, edge: &[(i32, i32, u8)], color: Color) -> Result<(), String> {
+    if edge.is_empty() {
+        return Ok(());
+    }
+    let (left, top) = edge.iter().fold((i32::MAX, i32::MAX), |(l, t), p| (l.min(p.0), t.min(p.1)));
+    let (right, bottom) = edge.iter().fold((i32::MIN, i32::MIN), |(r, b), p| (r.max(p.0), b.max(p.1)));
+    let width = (right - left + 1) as usize;
+    let area = Rect::new(left, top, width as u32, (bottom - top + 1) as u32);
+    let mut under = canvas.read_pixels(area, PixelFormatEnum::RGBA32)?;
+
+    let at = |x: i32, y: i32| ((y - top) as usize * width + (x - left) as usize) * 4;
+    // blended in place, so a pixel plotted twice blends over its first blend
+    for &(x, y, alpha) in edge.iter() {
+        let i = at(x, y);
+        let mut rgba = [0u8; 4];
+        rgba.copy_from_slice(&under[i..i + 4]);
+        under[i..i + 4].copy_from_slice(&blend_over(rgba, color, alpha));
+    }
+    let mut spots: Vec<(i32, i32)> = edge.iter().map(|p| (p.0, p.1)).collect();
+    spots.sort_unstable();
+    spots.dedup();
+    let mut by_color: BTreeMap<[u8; 4], Vec<Point>> = BTreeMap::new();
+    for (x, y) in spots {
+        let i = at(x, y);
+        by_color.entry([under[i], under[i + 1], under[i + 2], under[i + 3]]).or_default().push(Point::new(x, y));
+    }
+    for (rgba, points) in by_color.iter() {
+        canvas.set_draw_color(Color::RGBA(rgba[0], rgba[1], rgba[2], rgba[3]));
+        canvas.draw_points(&points[..])?;
+    }
+    Ok(())
+}
@@ -1,15 +1,22 @@
 extern crate sdl2;
 use sdl2::EventPump;
-use sdl2::rect::Rect;
 use sdl2::pixels::Color;
 use sdl2::render::WindowCanvas;
 
 
+use crate::jives::jiveconic::{Conic2D, Line2};
+use crate::jives::jivecurve::JiveCurve;
+use crate::jives::jivemesh::Mesh;
+use crate::jives::jivedandelin::DandelinScene;
 use crate::jives::jivesurface::JiveSurface;
 use crate::la::linear_algebra::Vec3f;
+use crate::la::linear_algebra::Mat3x3;
 use crate::la::linear_algebra::Mat4x4;
+use crate::la::linear_algebra::Vec4f;
 
-use crate::util::colors::find_color;
+use crate::util::backend::{pixel, RenderBackend};
+use crate::util::lighting::{default_lights, shade, Light, Material, Shading};
+use crate::util::shader::{Fragment, Position, Shader};
 use std::f32::consts::PI;
 
 pub fn util_function() {
@@ -46,6 +53,16 @@ pub struct ScreenData{
     pub field_of_view: f32,
     pub f_near: f32,
     pub f_far: f32,
+    // world -> camera, Mat4x4::look_at for a camera of your own
+    pub view: Mat4x4,
+}
+
+impl Default for ScreenData {
+    // 800x800, 80 degrees, the camera CAMERA_DISTANCE back from the origin looking at it
+    fn default() -> Self {
+        ScreenData{ screen_width: 800.0, screen_height: 800.0, field_of_view: 80.0, f_near: 1.0, f_far: 1000.0,
+                    view: Mat4x4::translation(&Vec3f::from(0.0, 0.0, CAMERA_DISTANCE)) }
+    }
 }
 
 impl ScreenData{
@@ -62,37 +79,403 @@ impl ScreenData{
         mat.e[14] = (-1.0 * self.f_far * self.f_near) / (self.f_far - self.f_near);
         return mat;
     }
+    // view then projection, what everything goes through after its own placement
+    pub fn view_projection(&self) -> Mat4x4 {
+        self.view * self.projection_matrix()
+    }
+    // where the camera is in the scene, the point the view takes to the origin
+    pub fn camera_position(&self) -> Result<Vec3f, String> {
+        let to_scene = self.view.inverse().ok_or("the view matrix squashes the scene flat, there is no camera position")?;
+        Ok(Vec3f::from(to_scene.e[12], to_scene.e[13], to_scene.e[14]))
+    }
+}
+
+
+// how far in front of the default camera the origin sits, the surfaces span about
+// -0.5,0.5 so all of them stay past f_near
+pub const CAMERA_DISTANCE: f32 = 2.0;
+
+// surface transformation -> placement, view and projection (to_clip) -> screen space
+// a point level with or behind the camera has nowhere to go and comes back as an Err
+// x and y come back in pixels, z is the projected depth the depth_ calls test and sort by
+pub fn project_vertex(vertex: &Vec3f, transformation: &Mat3x3, to_clip: &Mat4x4, screen_data: &ScreenData) -> Result<Vec3f, String> {
+    let mut render_vertex = *vertex;
+
+    // apply the surfaces transformation matrix
+    render_vertex *= transformation;
+    // into the scene, in front of the camera, through the projection and divide by w
+    let clip = Vec4f::point(&render_vertex) * to_clip;
+    render_vertex = clip.perspective_divide()?;
+
+    // add 1 to x and y
+    render_vertex.e[0] += 1.0; render_vertex.e[1] += 1.0;
+
+    // scale x by half screen width
+    render_vertex.e[0] *= screen_data.screen_width / 2.0;
+
+    // scale y by half screen height
+    render_vertex.e[1] *= screen_data.screen_height / 2.0;
+
+    Ok(render_vertex)
+}
+
+// connects consecutive projected points, a point behind the camera breaks the line
+fn segments(points: &[Result<Vec3f, String>]) -> impl Iterator<Item = (&Vec3f, &Vec3f)> + '_ {
+    points.windows(2).filter_map(|pair| match (&pair[0], &pair[1]) {
+        (Ok(p), Ok(q)) => Some((p, q)),
+        _ => None,
+    })
+}
+
+// the mesh vertices colored by the surface's shader, Position when it has none
+pub fn render_jive_surface<B: RenderBackend + ?Sized>(canvas: &mut B, jive_surface: &JiveSurface, screen_data: &ScreenData ) -> Result<(), String> {
+    // a grid under 2x2 (a single wrapping u sample) has no triangles, its points still draw
+    let mesh = jive_surface.mesh().or_else(|error| jive_surface.point_mesh().ok_or(error))?;
+    let shader: &dyn Shader = jive_surface.surface_shader.as_deref().unwrap_or(&Position);
+
+    for vertex in scene_vertices(jive_surface, &mesh, screen_data).iter().flatten() {
+        let color = shader.shade(&vertex.fragment(jive_surface.surface_id));
+        canvas.depth_point(&vertex.screen, 4, color)?;
+    }
+    Ok(())
+}
+
+// shaded triangles
+pub struct ShadingOptions {
+    pub shading: Shading,
+    // in the scene, after the placement and before the view
+    pub lights: Vec<Light>,
+}
+
+impl Default for ShadingOptions {
+    fn default() -> Self {
+        ShadingOptions{ shading: Shading::Phong, lights: default_lights() }
+    }
+}
+
+// a mesh vertex in the scene and on the screen, w is its distance in front of the camera
+struct SceneVertex {
+    scene: Vec3f,
+    normal: Vec3f,
+    uv: [f32; 2],
+    screen: Vec3f,
+    w: f32,
+}
+
+impl SceneVertex {
+    fn fragment(&self, surface_id: usize) -> Fragment {
+        Fragment{ position: self.scene, normal: self.normal.try_normalize().unwrap_or_default(), uv: self.uv,
+                  depth: self.screen.e[2], surface_id }
+    }
+}
+
+// the mesh through the surface's transformation and placement, None where a vertex is
+// behind the camera
+fn scene_vertices(jive_surface: &JiveSurface, mesh: &Mesh, screen_data: &ScreenData) -> Vec<Option<SceneVertex>> {
+    let transformation = jive_surface.transformation();
+    let placement = jive_surface.surface_placement;
+    let to_clip = screen_data.view_projection();
+    let turn_normal = normal_matrix(&transformation, &placement);
+
+    mesh.positions.iter().enumerate().map(|(i, position)| {
+        // the placement only moves and turns, w stays 1
+        let scene = (Vec4f::point(&(transformation * *position)) * &placement).perspective_divide().ok()?;
+        let screen = project_vertex(&scene, &Mat3x3::identity(), &to_clip, screen_data).ok()?;
+        let normal = turn_normal * mesh.normals.get(i).copied().unwrap_or_default();
+        let uv = mesh.uvs.get(i).copied().unwrap_or_default();
+        Some(SceneVertex{ scene, normal, uv, screen, w: (Vec4f::point(&scene) * &to_clip).w() })
+    }).collect()
 }
 
+// turns normals along with a surface: the inverse transpose of the transformation and the
+// placement together, so a squashed surface keeps its normals perpendicular to it
+fn normal_matrix(transformation: &Mat3x3, placement: &Mat4x4) -> Mat3x3 {
+    // the placement acts on row vectors, as a Mat3x3 acting on columns it is transposed
+    let mut turn = Mat3x3::identity();
+    for i in 0..3 {
+        for j in 0..3 {
+            turn.e[i * 3 + j] = placement.e[j * 4 + i];
+        }
+    }
+    let linear = turn * *transformation;
+    linear.inverse().map(|inverse| inverse.transpose()).unwrap_or(linear)
+}
 
-pub fn render_jive_surface(canvas: &mut WindowCanvas, jive_surface: &JiveSurface, screen_data: &ScreenData ) -> Result<(), String> {
-    let raw_vertex_data: Vec<Vec3f> = jive_surface.surface_data();
-    let mut render_vertex: Vec3f;
-    let mut position_vertex: Vec3f;
-    let projection_matrix = screen_data.projection_matrix();
+// screen weights to weights across the surface, closer corners count for more on screen
+fn perspective_weights(weights: [f32; 3], w: [f32; 3]) -> [f32; 3] {
+    let q = [weights[0] / w[0], weights[1] / w[1], weights[2] / w[2]];
+    let sum = q[0] + q[1] + q[2];
+    if sum == 0.0 || !sum.is_finite() {
+        return weights;
+    }
+    [q[0] / sum, q[1] / sum, q[2] / sum]
+}
 
-    for vertex in raw_vertex_data.iter() {
-        render_vertex = Vec3f{e : vertex.e};
-        position_vertex = Vec3f{e : vertex.e};
-        
-        // apply the surfaces transformation matrix
-        render_vertex *= &jive_surface.surface_transformation;
-        // multiply by projection mat
-        render_vertex *= &projection_matrix;
-        
-        // add 1 to x and y
-        render_vertex.e[0] += 1.0; render_vertex.e[1] += 1.0;
+// where a surface wraps its parameters jump from near 1 back to 0, the corners past the
+// jump are moved up by 1 so the triangle does not run back across the whole range
+fn unwrap_seam(mut uvs: [[f32; 2]; 3]) -> [[f32; 2]; 3] {
+    for k in 0..2 {
+        let top = uvs.iter().fold(f32::NEG_INFINITY, |m, uv| m.max(uv[k]));
+        for uv in uvs.iter_mut() {
+            if top - uv[k] > 0.5 {
+                uv[k] += 1.0;
+            }
+        }
+    }
+    uvs
+}
 
-        // scale x by half screen width
-        render_vertex.e[0] *= screen_data.screen_width / 2.0;
+fn blend(values: [f32; 3], weights: [f32; 3]) -> f32 {
+    values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+}
 
-        // scale y by half screen height
-        render_vertex.e[1] *= screen_data.screen_height / 2.0;
+// triangles lit by options.lights, the surface's shader (if it has one) gives the base color
+pub fn render_jive_shaded<B: RenderBackend + ?Sized>(canvas: &mut B, jive_surface: &JiveSurface, screen_data: &ScreenData, options: &ShadingOptions) -> Result<(), String> {
+    let mesh = jive_surface.mesh()?;
+    let vertices = scene_vertices(jive_surface, &mesh, screen_data);
+    let eye = screen_data.camera_position()?;
+    let lights = &options.lights[..];
+    let surface_id = jive_surface.surface_id;
+    // the material with the shader's color at the fragment
+    let material = |fragment: &Fragment| match &jive_surface.surface_shader {
+        Some(shader) => Material{ base_color: shader.shade(fragment), ..jive_surface.surface_material },
+        None => jive_surface.surface_material,
+    };
 
-        // render the point and find the color
-        let (r,g,b) = find_color(&position_vertex);
-        canvas.set_draw_color(Color::RGB(r, g, b));
-        canvas.fill_rect(Rect::new(render_vertex.e[0] as i32, render_vertex.e[1] as i32, 4, 4))?;
+    for &[i, j, k] in mesh.indices.iter() {
+        // a corner behind the camera takes the whole triangle with it
+        let (Some(a), Some(b), Some(c)) = (&vertices[i], &vertices[j], &vertices[k]) else {
+            continue;
+        };
+        let corners = [a, b, c];
+        let w = [a.w, b.w, c.w];
+        let uvs = unwrap_seam([a.uv, b.uv, c.uv]);
+        let depths = [a.screen.e[2], b.screen.e[2], c.screen.e[2]];
+        match options.shading {
+            Shading::Flat => {
+                let third = [1.0 / 3.0; 3];
+                let position = (a.scene + b.scene + c.scene) / 3.0;
+                let normal = (b.scene - a.scene).cross(&(c.scene - a.scene));
+                let uv = [blend([uvs[0][0], uvs[1][0], uvs[2][0]], third), blend([uvs[0][1], uvs[1][1], uvs[2][1]], third)];
+                let fragment = Fragment{ position, normal: normal.try_normalize().unwrap_or_default(), uv,
+                                         depth: blend(depths, third), surface_id };
+                canvas.depth_triangle(&a.screen, &b.screen, &c.screen, shade(&material(&fragment), lights, &position, &normal, &eye))?;
+            }
+            Shading::Gouraud => {
+                let colors = [0, 1, 2].map(|n| {
+                    let fragment = Fragment{ uv: uvs[n], ..corners[n].fragment(surface_id) };
+                    shade(&material(&fragment), lights, &corners[n].scene, &corners[n].normal, &eye)
+                });
+                canvas.shaded_triangle(&a.screen, &b.screen, &c.screen, &mut |weights| {
+                    let weights = perspective_weights(weights, w);
+                    let mix = |channel: fn(&Color) -> u8| {
+                        let sum: f32 = colors.iter().zip(weights.iter()).map(|(color, w)| channel(color) as f32 * w).sum();
+                        sum.round().clamp(0.0, 255.0) as u8
+                    };
+                    Color::RGBA(mix(|c| c.r), mix(|c| c.g), mix(|c| c.b), mix(|c| c.a))
+                })?;
+            }
+            Shading::Phong => {
+                canvas.shaded_triangle(&a.screen, &b.screen, &c.screen, &mut |weights| {
+                    let across = perspective_weights(weights, w);
+                    let [wa, wb, wc] = across;
+                    let position = a.scene * wa + b.scene * wb + c.scene * wc;
+                    let normal = a.normal * wa + b.normal * wb + c.normal * wc;
+                    let uv = [blend([uvs[0][0], uvs[1][0], uvs[2][0]], across), blend([uvs[0][1], uvs[1][1], uvs[2][1]], across)];
+                    // the projected depth is straight across the screen, not across the surface
+                    let fragment = Fragment{ position, normal: normal.try_normalize().unwrap_or_default(), uv,
+                                             depth: blend(depths, weights), surface_id };
+                    shade(&material(&fragment), lights, &position, &normal, &eye)
+                })?;
+            }
+        }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// wireframe
+pub struct WireframeOptions {
+    // how many constant-u and constant-v lines to draw
+    pub u_lines: usize,
+    pub v_lines: usize,
+    pub color: Color,
+}
+
+impl Default for WireframeOptions {
+    fn default() -> Self {
+        WireframeOptions{ u_lines: 24, v_lines: 12, color: Color::RGB(200, 200, 200) }
+    }
+}
+
+pub fn render_jive_wireframe<B: RenderBackend + ?Sized>(canvas: &mut B, jive_surface: &JiveSurface, screen_data: &ScreenData, options: &WireframeOptions) -> Result<(), String> {
+    let to_clip = jive_surface.surface_placement * screen_data.view_projection();
+    let transformation = jive_surface.transformation();
+
+    for line in jive_surface.isolines(options.u_lines, options.v_lines).iter() {
+        let screen_points: Vec<Result<Vec3f, String>> = line.iter()
+            .map(|vertex| project_vertex(vertex, &transformation, &to_clip, screen_data))
+            .collect();
+
+        for (from, to) in segments(&screen_points) {
+            canvas.depth_line(from, to, 1, options.color)?;
+        }
+    }
+    Ok(())
+}
+
+// curves
+pub struct CurveStyle {
+    // line width in pixels, 1 draws antialiased
+    pub thickness: u8,
+    pub color: Color,
+    // pulls the curve toward the camera by this much projected depth, so a curve lying on
+    // a surface is not hidden by the surface's own lines where they cross
+    pub depth_bias: f32,
+}
+
+impl Default for CurveStyle {
+    fn default() -> Self {
+        CurveStyle{ thickness: 2, color: Color::RGB(255, 255, 255), depth_bias: CURVE_DEPTH_BIAS }
+    }
+}
+
+// enough to cover how far the polylines sag off the true surface at the usual sampling
+pub const CURVE_DEPTH_BIAS: f32 = 0.002;
+
+pub fn render_jive_curve<B: RenderBackend + ?Sized>(canvas: &mut B, jive_curve: &JiveCurve, screen_data: &ScreenData, style: &CurveStyle) -> Result<(), String> {
+    let to_clip = jive_curve.curve_placement * screen_data.view_projection();
+    let transformation = jive_curve.transformation();
+    let screen_points: Vec<Result<Vec3f, String>> = jive_curve.polyline().iter()
+        .map(|vertex| project_vertex(vertex, &transformation, &to_clip, screen_data))
+        .map(|p| p.map(|mut p| { p.e[2] -= style.depth_bias; p }))
+        .collect();
+
+    for (from, to) in segments(&screen_points) {
+        canvas.depth_line(from, to, style.thickness, style.color)?;
+    }
+    Ok(())
+}
+
+// 2d conics, drawn flat with y up instead of through the projection
+pub struct ConicPlotStyle {
+    // half the width of the view in the conic's own units
+    pub reach: f32,
+    pub thickness: u8,
+    pub conic: Color,
+    pub axes: Color,
+    // directrices and asymptotes
+    pub guides: Color,
+    // center, vertices and foci
+    pub points: Color,
+    pub label: Color,
+}
+
+impl Default for ConicPlotStyle {
+    fn default() -> Self {
+        ConicPlotStyle{ reach: 10.0, thickness: 2, conic: Color::RGB(255, 200, 60), axes: Color::RGB(120, 120, 120),
+                        guides: Color::RGB(90, 140, 220), points: Color::RGB(240, 90, 90), label: Color::RGB(220, 220, 220) }
+    }
+}
+
+pub fn render_conic_plot<B: RenderBackend + ?Sized>(canvas: &mut B, conic: &Conic2D, screen_data: &ScreenData, style: &ConicPlotStyle) -> Result<(), String> {
+    let (width, height) = (screen_data.screen_width, screen_data.screen_height);
+    let to_screen = |p: [f32; 2]| -> (i32, i32) {
+        let x = width / 2.0 + p[0] * width / (2.0 * style.reach);
+        let y = height / 2.0 - p[1] * height / (2.0 * style.reach);
+        (x.floor() as i32, y.floor() as i32)
+    };
+    let draw_line = |canvas: &mut B, line: &Line2, color: Color| -> Result<(), String> {
+        let half = 4.0 * style.reach + line.point[0].abs() + line.point[1].abs();
+        let (x1, y1) = to_screen([line.point[0] - half * line.direction[0], line.point[1] - half * line.direction[1]]);
+        let (x2, y2) = to_screen([line.point[0] + half * line.direction[0], line.point[1] + half * line.direction[1]]);
+        canvas.line((x1, y1), (x2, y2), 1, color)
+    };
+
+    // axes with a tick every unit
+    draw_line(canvas, &Line2{ point: [0.0, 0.0], direction: [1.0, 0.0] }, style.axes)?;
+    draw_line(canvas, &Line2{ point: [0.0, 0.0], direction: [0.0, 1.0] }, style.axes)?;
+    for k in (-style.reach.floor() as i32)..=(style.reach.floor() as i32) {
+        let (x, y) = to_screen([k as f32, k as f32]);
+        let (ox, oy) = to_screen([0.0, 0.0]);
+        canvas.line((x, oy - 3), (x, oy + 3), 1, style.axes)?;
+        canvas.line((ox - 3, y), (ox + 3, y), 1, style.axes)?;
+    }
+
+    for line in conic.directrices.iter().chain(conic.asymptotes.iter()) {
+        draw_line(canvas, line, style.guides)?;
+    }
+
+    for polyline in conic.polylines(style.reach, 360).iter() {
+        if polyline.len() == 1 {
+            let (x, y) = to_screen(polyline[0]);
+            canvas.filled_circle((x, y), style.thickness as i32 + 2, style.conic)?;
+        }
+        for pair in polyline.windows(2) {
+            canvas.line(to_screen(pair[0]), to_screen(pair[1]), style.thickness, style.conic)?;
+        }
+    }
+
+    // filled foci and center, hollow vertices
+    for point in conic.foci.iter().chain(conic.center.iter()) {
+        let (x, y) = to_screen(*point);
+        canvas.filled_circle((x, y), 4, style.points)?;
+    }
+    for point in conic.vertices.iter() {
+        let (x, y) = to_screen(*point);
+        canvas.circle((x, y), 5, style.points)?;
+    }
+
+    let label = match conic.eccentricity {
+        Some(e) => format!("{:?}  e = {:.4}", conic.class, e),
+        None => format!("{:?}", conic.class),
+    };
+    canvas.text(8, 8, &label, style.label)?;
+    Ok(())
+}
+
+// dandelin spheres
+pub struct DandelinStyle {
+    pub cone: WireframeOptions,
+    pub plane: WireframeOptions,
+    pub spheres: WireframeOptions,
+    pub section: CurveStyle,
+    // the circles where the spheres touch the cone
+    pub tangency: CurveStyle,
+    pub foci: Color,
+}
+
+impl Default for DandelinStyle {
+    fn default() -> Self {
+        DandelinStyle{
+            cone: WireframeOptions{ u_lines: 24, v_lines: 8, color: Color::RGB(90, 90, 110) },
+            plane: WireframeOptions{ u_lines: 10, v_lines: 10, color: Color::RGB(70, 110, 70) },
+            spheres: WireframeOptions{ u_lines: 16, v_lines: 8, color: Color::RGB(110, 150, 200) },
+            section: CurveStyle{ thickness: 3, color: Color::RGB(255, 200, 60), depth_bias: CURVE_DEPTH_BIAS },
+            tangency: CurveStyle{ thickness: 3, color: Color::RGB(240, 110, 200), depth_bias: CURVE_DEPTH_BIAS },
+            foci: Color::RGB(240, 90, 90),
+        }
+    }
+}
+
+pub fn render_dandelin_scene<B: RenderBackend + ?Sized>(canvas: &mut B, scene: &DandelinScene, screen_data: &ScreenData, style: &DandelinStyle) -> Result<(), String> {
+    render_jive_wireframe(canvas, &scene.cone, screen_data, &style.cone)?;
+    render_jive_wireframe(canvas, &scene.plane, screen_data, &style.plane)?;
+    for sphere in scene.sphere_surfaces.iter() {
+        render_jive_wireframe(canvas, sphere, screen_data, &style.spheres)?;
+    }
+    for curve in scene.section_curves.iter() {
+        render_jive_curve(canvas, curve, screen_data, &style.section)?;
+    }
+    for circle in scene.tangency_circles.iter() {
+        render_jive_curve(canvas, circle, screen_data, &style.tangency)?;
+    }
+
+    let to_clip = screen_data.view_projection();
+    for focus in scene.foci().iter() {
+        let Ok(p) = project_vertex(focus, &scene.scene_transformation, &to_clip, screen_data) else {
+            continue;
+        };
+        canvas.filled_circle(pixel(&p), 5, style.foci)?;
+    }
+    Ok(())
+}
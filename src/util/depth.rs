@@ -0,0 +1,130 @@
+/*
+----- Depth sorted -----
+painter's order for backends without a z-buffer, like the SDL canvas and SvgDocument.
+Wrapped in a DepthSorted, the depth_ calls are held back and drawn farthest first when the
+frame is flushed, so the near side of a surface ends up over the far side
+
+    ----- Order -----
+    each primitive goes by the mean projected z of its corners, primitives at the same
+    depth keep their call order. A call without a depth (a label, a marker) flushes what
+    is queued first, so whatever is drawn after the scene stays on top of it
+
+    anything still queued when a DepthSorted is dropped is drawn then, but an error from
+    the backend has nowhere to go, end the frame with present or flush to see it
+*/
+
+extern crate sdl2;
+use sdl2::pixels::Color;
+
+use crate::la::linear_algebra::Vec3f;
+use crate::util::backend::RenderBackend;
+
+#[derive(Copy, Clone, Debug)]
+enum Queued {
+    Point { at: Vec3f, size: u32, color: Color },
+    Line { from: Vec3f, to: Vec3f, thickness: u8, color: Color },
+    Triangle { corners: [Vec3f; 3], color: Color },
+}
+
+impl Queued {
+    fn depth(&self) -> f32 {
+        match self {
+            Queued::Point{ at, .. } => at.e[2],
+            Queued::Line{ from, to, .. } => (from.e[2] + to.e[2]) / 2.0,
+            Queued::Triangle{ corners, .. } => corners.iter().map(|c| c.e[2]).sum::<f32>() / 3.0,
+        }
+    }
+}
+
+pub struct DepthSorted<'a, B: RenderBackend + ?Sized> {
+    backend: &'a mut B,
+    queue: Vec<Queued>,
+}
+
+impl<'a, B: RenderBackend + ?Sized> DepthSorted<'a, B> {
+    pub fn new(backend: &'a mut B) -> DepthSorted<'a, B> {
+        DepthSorted{ backend, queue: Vec::new() }
+    }
+    /// Draws everything queued, farthest first.
+    pub fn flush(&mut self) -> Result<(), String> {
+        let mut queue = std::mem::take(&mut self.queue);
+        // sort_by is stable, ties stay in call order
+        queue.sort_by(|p, q| q.depth().total_cmp(&p.depth()));
+        for queued in queue.iter() {
+            match *queued {
+                Queued::Point{ at, size, color } => self.backend.depth_point(&at, size, color)?,
+                Queued::Line{ from, to, thickness, color } => self.backend.depth_line(&from, &to, thickness, color)?,
+                Queued::Triangle{ corners: [a, b, c], color } => self.backend.depth_triangle(&a, &b, &c, color)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B: RenderBackend + ?Sized> Drop for DepthSorted<'_, B> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<B: RenderBackend + ?Sized> RenderBackend for DepthSorted<'_, B> {
+    fn size(&self) -> Result<(u32, u32), String> {
+        self.backend.size()
+    }
+    fn plot(&mut self, pixels: &[(i32, i32, u8)], color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.plot(pixels, color)
+    }
+    /// Drops whatever is queued along with the old frame.
+    fn clear(&mut self, color: Color) -> Result<(), String> {
+        self.queue.clear();
+        self.backend.clear(color)
+    }
+    fn present(&mut self) -> Result<(), String> {
+        self.flush()?;
+        self.backend.present()
+    }
+
+    // the rest go straight through, so the backend draws them its own way
+    fn point(&mut self, x: i32, y: i32, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.point(x, y, color)
+    }
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.fill_rect(x, y, width, height, color)
+    }
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), thickness: u8, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.line(from, to, thickness, color)
+    }
+    fn filled_triangle(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32), color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.filled_triangle(a, b, c, color)
+    }
+    fn filled_circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.filled_circle(center, radius, color)
+    }
+    fn circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.circle(center, radius, color)
+    }
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color) -> Result<(), String> {
+        self.flush()?;
+        self.backend.text(x, y, text, color)
+    }
+
+    fn depth_point(&mut self, at: &Vec3f, size: u32, color: Color) -> Result<(), String> {
+        self.queue.push(Queued::Point{ at: *at, size, color });
+        Ok(())
+    }
+    fn depth_line(&mut self, from: &Vec3f, to: &Vec3f, thickness: u8, color: Color) -> Result<(), String> {
+        self.queue.push(Queued::Line{ from: *from, to: *to, thickness, color });
+        Ok(())
+    }
+    fn depth_triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, color: Color) -> Result<(), String> {
+        self.queue.push(Queued::Triangle{ corners: [*a, *b, *c], color });
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+/*
+----- Framebuffer -----
+an RGBA image in memory to render into without a window, for tests, servers and CI.
+It is a RenderBackend like the SDL canvas and comes out with the same pixels
+
+    rows top to bottom, 4 bytes per pixel (r, g, b, a)
+
+    ----- Depth -----
+    made with_depth it also keeps a z-buffer, one f32 per pixel starting out infinitely far.
+    A depth_ call only lands on a pixel nearer than what is there and, where it covers the
+    pixel fully, leaves its depth behind. The other calls draw over everything and leave
+    the depth alone
+*/
+
+extern crate sdl2;
+use sdl2::pixels::Color;
+use std::fs;
+use std::path::Path;
+
+use crate::la::linear_algebra::Vec3f;
+use crate::util::backend::{blend_over, coverage_alpha, line_pixels, pixel, rasterize, RenderBackend};
+use crate::util::raster;
+
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    depth: Option<Vec<f32>>,
+}
+
+impl Framebuffer {
+    /// An opaque black image.
+    pub fn new(width: u32, height: u32) -> Result<Framebuffer, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("a framebuffer needs pixels, got {width}x{height}"));
+        }
+        let mut framebuffer = Framebuffer{ width, height, pixels: vec![0; width as usize * height as usize * 4], depth: None };
+        framebuffer.clear(Color::RGB(0, 0, 0));
+        Ok(framebuffer)
+    }
+    /// An opaque black image with a z-buffer.
+    pub fn with_depth(width: u32, height: u32) -> Result<Framebuffer, String> {
+        let mut framebuffer = Framebuffer::new(width, height)?;
+        framebuffer.depth = Some(vec![f32::INFINITY; width as usize * height as usize]);
+        Ok(framebuffer)
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// The raw RGBA bytes.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+    /// Also sends the z-buffer back to infinitely far.
+    pub fn clear(&mut self, color: Color) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+        if let Some(depth) = self.depth.as_mut() {
+            depth.fill(f32::INFINITY);
+        }
+    }
+    /// None off the image.
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        let i = self.index(x, y)?;
+        let p = &self.pixels[i..i + 4];
+        Some(Color::RGBA(p[0], p[1], p[2], p[3]))
+    }
+    /// None off the image or without a z-buffer.
+    pub fn depth(&self, x: i32, y: i32) -> Option<f32> {
+        let i = self.index(x, y)?;
+        self.depth.as_ref().map(|depth| depth[i / 4])
+    }
+
+    // same arithmetic as SDL's software BLENDMODE_BLEND: src * a + dst * (255 - a), each
+    // product divided by 255 and truncated
+    fn blend(&mut self, x: i32, y: i32, color: Color, coverage: u8) {
+        let Some(i) = self.index(x, y) else {
+            return;
+        };
+        let mut under = [0u8; 4];
+        under.copy_from_slice(&self.pixels[i..i + 4]);
+        self.pixels[i..i + 4].copy_from_slice(&blend_over(under, color, coverage_alpha(color, coverage)));
+    }
+    // the pixels nearer than the z-buffer, depth(x, y) at each pixel center. The color is
+    // only asked for the pixels that pass, and only a fully covered pixel leaves its depth
+    // behind: an antialiased edge blends in without hiding what is drawn behind it later
+    fn plot_nearer(&mut self, pixels: &[(i32, i32, u8)], depth: impl Fn(f32, f32) -> f32, mut color: impl FnMut(f32, f32) -> Color) {
+        for &(x, y, coverage) in pixels.iter() {
+            let (Some(i), Some(buffer)) = (self.index(x, y), self.depth.as_mut()) else {
+                continue;
+            };
+            let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+            let z = depth(cx, cy);
+            // a NaN is never nearer
+            if z.is_nan() || z >= buffer[i / 4] {
+                continue;
+            }
+            if coverage == 255 {
+                buffer[i / 4] = z;
+            }
+            self.blend(x, y, color(cx, cy), coverage);
+        }
+    }
+    // a triangle through plot_nearer, or over everything without a z-buffer
+    fn triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, shade: &mut dyn FnMut([f32; 3]) -> Color) -> Result<(), String> {
+        let (e1, e2) = (*b - *a, *c - *a);
+        let area = e1.e[0] * e2.e[1] - e2.e[0] * e1.e[1];
+        if area == 0.0 || !area.is_finite() {
+            return Ok(());
+        }
+        // the weights of b and c at (x, y), z/w is linear across the screen so they give it too
+        let weights = |x: f32, y: f32| {
+            let (px, py) = (x - a.e[0], y - a.e[1]);
+            ((px * e2.e[1] - e2.e[0] * py) / area, (e1.e[0] * py - px * e1.e[1]) / area)
+        };
+        let corners = [(a.e[0], a.e[1]), (b.e[0], b.e[1]), (c.e[0], c.e[1])];
+        let pixels = rasterize(self.size()?, |clip, plot| raster::fill_polygon(clip, &corners, plot));
+        let mut color = |x: f32, y: f32| {
+            let (s, t) = weights(x, y);
+            shade([1.0 - s - t, s, t])
+        };
+        if self.depth.is_none() {
+            for &(x, y, coverage) in pixels.iter() {
+                let color = color(x as f32 + 0.5, y as f32 + 0.5);
+                self.blend(x, y, color, coverage);
+            }
+            return Ok(());
+        }
+        self.plot_nearer(&pixels, |x, y| {
+            let (s, t) = weights(x, y);
+            a.e[2] + s * e1.e[2] + t * e2.e[2]
+        }, color);
+        Ok(())
+    }
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        Some((y as usize * self.width as usize + x as usize) * 4)
+    }
+
+    /// A binary PPM (P6), alpha dropped. Opens in most image viewers.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for pixel in self.pixels.chunks_exact(4) {
+            out.extend_from_slice(&pixel[..3]);
+        }
+        out
+    }
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        fs::write(path.as_ref(), self.to_ppm()).map_err(|e| format!("could not write {}: {e}", path.as_ref().display()))
+    }
+}
+
+impl RenderBackend for Framebuffer {
+    fn size(&self) -> Result<(u32, u32), String> {
+        Ok((self.width, self.height))
+    }
+    fn plot(&mut self, pixels: &[(i32, i32, u8)], color: Color) -> Result<(), String> {
+        for &(x, y, coverage) in pixels.iter() {
+            self.blend(x, y, color, coverage);
+        }
+        Ok(())
+    }
+    fn clear(&mut self, color: Color) -> Result<(), String> {
+        Framebuffer::clear(self, color);
+        Ok(())
+    }
+
+    fn depth_point(&mut self, at: &Vec3f, size: u32, color: Color) -> Result<(), String> {
+        let (x, y) = pixel(at);
+        if self.depth.is_none() {
+            return self.fill_rect(x, y, size, size, color);
+        }
+        let pixels = rasterize(self.size()?, |clip, plot| raster::fill_rect(clip, x, y, size, size, plot));
+        self.plot_nearer(&pixels, |_, _| at.e[2], |_, _| color);
+        Ok(())
+    }
+    fn depth_line(&mut self, from: &Vec3f, to: &Vec3f, thickness: u8, color: Color) -> Result<(), String> {
+        if self.depth.is_none() {
+            return self.line(pixel(from), pixel(to), thickness, color);
+        }
+        let pixels = line_pixels(self.size()?, pixel(from), pixel(to), thickness);
+        // along the line between the two end pixel centers
+        let (a, b) = (pixel(from), pixel(to));
+        let (ax, ay) = (a.0 as f32 + 0.5, a.1 as f32 + 0.5);
+        let (dx, dy) = (b.0 as f32 - a.0 as f32, b.1 as f32 - a.1 as f32);
+        let length = dx * dx + dy * dy;
+        self.plot_nearer(&pixels, |x, y| {
+            let t = if length == 0.0 { 0.0 } else { (((x - ax) * dx + (y - ay) * dy) / length).clamp(0.0, 1.0) };
+            from.e[2] + t * (to.e[2] - from.e[2])
+        }, |_, _| color);
+        Ok(())
+    }
+    fn depth_triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, color: Color) -> Result<(), String> {
+        if self.depth.is_none() {
+            return self.filled_triangle((a.e[0], a.e[1]), (b.e[0], b.e[1]), (c.e[0], c.e[1]), color);
+        }
+        self.triangle(a, b, c, &mut |_| color)
+    }
+    fn shaded_triangle(&mut self, a: &Vec3f, b: &Vec3f, c: &Vec3f, shade: &mut dyn FnMut([f32; 3]) -> Color) -> Result<(), String> {
+        self.triangle(a, b, c, shade)
+    }
+}
@@ -0,0 +1,137 @@
+/*
+----- Lighting -----
+lights, materials and the shading model render_jive_shaded colors triangles with
+
+    ----- Model -----
+    Blinn-Phong: every light adds base * diffuse * (n . l) and specular * (n . h)^shininess,
+    h halfway between the way to the light and the way to the eye, and ambient light adds
+    base * intensity. Everything is in scene space, after the placement and before the view,
+    and the channels are clamped at full
+
+    ----- Two sided -----
+    the open surfaces (planes, cones, the mobius strip) are seen from both sides, so a
+    normal facing away from the eye is turned around before it is lit
+
+    ----- Shading -----
+    Flat lights each triangle once at its middle, Gouraud lights the corners and blends the
+    colors, Phong blends the positions and normals and lights every pixel. Gouraud and Phong
+    need a backend that colors per pixel (the Framebuffer), the others fill each triangle
+    with the color at its middle
+*/
+
+extern crate sdl2;
+use sdl2::pixels::Color;
+
+use crate::la::linear_algebra::Vec3f;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Light {
+    /// lights everything evenly, from no direction
+    Ambient { color: Color, intensity: f32 },
+    /// a far away light like the sun, `direction` is the way its light travels
+    Directional { direction: Vec3f, color: Color, intensity: f32 },
+    /// a light at `position` shining every way, it does not fade with distance
+    Point { position: Vec3f, color: Color, intensity: f32 },
+}
+
+impl Light {
+    pub fn ambient(intensity: f32) -> Light {
+        Light::Ambient{ color: Color::RGB(255, 255, 255), intensity }
+    }
+    pub fn directional(direction: Vec3f, intensity: f32) -> Result<Light, String> {
+        let direction = direction.try_normalize()
+            .ok_or(format!("a directional light needs a direction, got {:?}", direction.e))?;
+        Ok(Light::Directional{ direction, color: Color::RGB(255, 255, 255), intensity })
+    }
+    pub fn point(position: Vec3f, intensity: f32) -> Light {
+        Light::Point{ position, color: Color::RGB(255, 255, 255), intensity }
+    }
+}
+
+/// A little ambient light and a white light from above and left of the default camera.
+pub fn default_lights() -> Vec<Light> {
+    vec![
+        Light::ambient(0.15),
+        Light::Directional{ direction: Vec3f::from(1.0, 1.0, 1.0).normalize(), color: Color::RGB(255, 255, 255), intensity: 0.9 },
+    ]
+}
+
+/// How a surface takes the light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Material {
+    pub base_color: Color,
+    /// how much of the base color comes back from a light, 0 to 1
+    pub diffuse: f32,
+    /// how bright the highlights are, 0 for none
+    pub specular: f32,
+    /// how tight the highlights are, bigger is shinier
+    pub shininess: f32,
+}
+
+impl Material {
+    /// The default finish in another color.
+    pub fn new(base_color: Color) -> Material {
+        Material{ base_color, ..Material::default() }
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material{ base_color: Color::RGB(200, 200, 200), diffuse: 0.8, specular: 0.3, shininess: 32.0 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shading {
+    Flat,
+    Gouraud,
+    Phong,
+}
+
+// a color as 0,1 floats
+fn channels(color: Color) -> Vec3f {
+    Vec3f::from(color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0)
+}
+// one color lit by another, channel by channel
+fn tint(a: &Vec3f, b: &Vec3f) -> Vec3f {
+    Vec3f::from(a.e[0] * b.e[0], a.e[1] * b.e[1], a.e[2] * b.e[2])
+}
+
+/// The color of the surface at `position` with `normal`, seen from `eye`. The normal does
+/// not have to be a unit vector.
+pub fn shade(material: &Material, lights: &[Light], position: &Vec3f, normal: &Vec3f, eye: &Vec3f) -> Color {
+    let base = channels(material.base_color);
+    let to_eye = (*eye - *position).try_normalize().unwrap_or(Vec3f::from(0.0, 0.0, -1.0));
+    let normal = match normal.try_normalize() {
+        Some(n) if n.dot(&to_eye) < 0.0 => -n,
+        Some(n) => n,
+        // no normal, light it like it faces the eye
+        None => to_eye,
+    };
+
+    let mut total = Vec3f::from(0.0, 0.0, 0.0);
+    for light in lights.iter() {
+        let (to_light, color, intensity) = match *light {
+            Light::Ambient{ color, intensity } => {
+                total += tint(&base, &channels(color)) * intensity;
+                continue;
+            }
+            Light::Directional{ direction, color, intensity } => (-direction, color, intensity),
+            Light::Point{ position: at, color, intensity } => match (at - *position).try_normalize() {
+                Some(to_light) => (to_light, color, intensity),
+                None => continue,
+            },
+        };
+        let facing = normal.dot(&to_light);
+        if facing <= 0.0 {
+            continue;
+        }
+        let light = channels(color) * intensity;
+        total += tint(&base, &light) * (material.diffuse * facing);
+        if let Some(half) = (to_light + to_eye).try_normalize() {
+            total += light * (material.specular * normal.dot(&half).max(0.0).powf(material.shininess));
+        }
+    }
+    let channel = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::RGBA(channel(total.e[0]), channel(total.e[1]), channel(total.e[2]), material.base_color.a)
+}
@@ -0,0 +1,176 @@
+/*
+----- Raster -----
+turns points, lines, circles, polygons and text into pixels. Every raster backend draws
+through these, so a scene comes out with the same pixels in a window as in a framebuffer
+
+    ----- Plots -----
+    each function calls plot(x, y, coverage) once per pixel it touches, coverage 255 for a
+    pixel that is fully inside and less along an antialiased edge. Only pixels on the
+    target (0 <= x < width, 0 <= y < height) are plotted, and the loops only run over
+    those, so a line out to a point just beside the camera costs no more than one across
+    the screen
+
+    ----- Coordinates -----
+    integer coordinates name pixels. The polygon fill takes f32 coordinates where pixel
+    (x, y) covers [x, x + 1) x [y, y + 1) and is filled when its center is inside
+*/
+
+use crate::util::font::{glyph, GLYPH_SIZE};
+
+/// The size of the target being drawn on.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Clip {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Clip {
+    fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64
+    }
+}
+
+// plots (x, y) if it is on the target
+fn put(clip: &Clip, plot: &mut dyn FnMut(i32, i32, u8), x: i64, y: i64, coverage: u8) {
+    if clip.contains(x, y) {
+        plot(x as i32, y as i32, coverage);
+    }
+}
+
+pub fn fill_rect(clip: &Clip, x: i32, y: i32, width: u32, height: u32, plot: &mut dyn FnMut(i32, i32, u8)) {
+    let (left, top) = ((x as i64).max(0), (y as i64).max(0));
+    let right = (x as i64 + width as i64).min(clip.width as i64);
+    let bottom = (y as i64 + height as i64).min(clip.height as i64);
+    for row in top..bottom {
+        for column in left..right {
+            plot(column as i32, row as i32, 255);
+        }
+    }
+}
+
+/// Wu's antialiased line, both ends included.
+pub fn aa_line(clip: &Clip, from: (i32, i32), to: (i32, i32), plot: &mut dyn FnMut(i32, i32, u8)) {
+    let (mut x0, mut y0, mut x1, mut y1) = (from.0 as i64, from.1 as i64, to.0 as i64, to.1 as i64);
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+    let gradient = if x1 == x0 { 0.0 } else { (y1 - y0) as f64 / (x1 - x0) as f64 };
+    let mut put_unswapped = |major: i64, minor: i64, coverage: u8| {
+        if steep { put(clip, plot, minor, major, coverage) } else { put(clip, plot, major, minor, coverage) }
+    };
+    // only the stretch of the major axis that is on the target
+    let extent = if steep { clip.height } else { clip.width } as i64;
+    for x in x0.max(0)..=x1.min(extent - 1) {
+        let y = y0 as f64 + gradient * (x - x0) as f64;
+        let below = y.floor();
+        let fraction = y - below;
+        put_unswapped(x, below as i64, (255.0 * (1.0 - fraction)).round() as u8);
+        if fraction > 0.0 {
+            put_unswapped(x, below as i64 + 1, (255.0 * fraction).round() as u8);
+        }
+    }
+}
+
+/// A line `thickness` pixels wide with square ends, both end pixels included.
+pub fn thick_line(clip: &Clip, from: (i32, i32), to: (i32, i32), thickness: u8, plot: &mut dyn FnMut(i32, i32, u8)) {
+    let half = thickness as f32 / 2.0;
+    let (x0, y0) = (from.0 as f32 + 0.5, from.1 as f32 + 0.5);
+    let (x1, y1) = (to.0 as f32 + 0.5, to.1 as f32 + 0.5);
+    let length = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+    if length == 0.0 {
+        fill_polygon(clip, &[(x0 - half, y0 - half), (x0 + half, y0 - half), (x0 + half, y0 + half), (x0 - half, y0 + half)], plot);
+        return;
+    }
+    // across the line, and half a pixel along it so both end pixels are in like aa_line
+    let (nx, ny) = (-(y1 - y0) / length * half, (x1 - x0) / length * half);
+    let (ex, ey) = ((x1 - x0) / length * 0.5, (y1 - y0) / length * 0.5);
+    let (x0, y0, x1, y1) = (x0 - ex, y0 - ey, x1 + ex, y1 + ey);
+    fill_polygon(clip, &[(x0 + nx, y0 + ny), (x1 + nx, y1 + ny), (x1 - nx, y1 - ny), (x0 - nx, y0 - ny)], plot);
+}
+
+// pixels at (dx, dy) from the center with dx^2 + dy^2 <= r^2 + r are in the disk, the + r
+// rounds the outline instead of leaving single pixels sticking out at the four tips
+fn disk_half_width(radius: i64, dy: i64) -> i64 {
+    let inside = radius * radius + radius - dy * dy;
+    if inside < 0 { -1 } else { (inside as f64).sqrt() as i64 }
+}
+
+// the rows of a circle that are on the target, as offsets from the center
+fn circle_rows(clip: &Clip, center: (i32, i32), radius: i64) -> std::ops::RangeInclusive<i64> {
+    let cy = center.1 as i64;
+    (-radius).max(-cy)..=radius.min(clip.height as i64 - 1 - cy)
+}
+
+pub fn filled_circle(clip: &Clip, center: (i32, i32), radius: i32, plot: &mut dyn FnMut(i32, i32, u8)) {
+    let (cx, cy, radius) = (center.0 as i64, center.1 as i64, radius as i64);
+    for dy in circle_rows(clip, center, radius) {
+        let half = disk_half_width(radius, dy);
+        for x in (cx - half).max(0)..=(cx + half).min(clip.width as i64 - 1) {
+            plot(x as i32, (cy + dy) as i32, 255);
+        }
+    }
+}
+
+/// The one pixel ring of the disk of `radius`.
+pub fn circle(clip: &Clip, center: (i32, i32), radius: i32, plot: &mut dyn FnMut(i32, i32, u8)) {
+    let (cx, cy, radius) = (center.0 as i64, center.1 as i64, radius as i64);
+    for dy in circle_rows(clip, center, radius) {
+        let outer = disk_half_width(radius, dy);
+        let inner = if radius > 0 { disk_half_width(radius - 1, dy) } else { -1 };
+        for x in (cx - outer).max(0)..=(cx + outer).min(clip.width as i64 - 1) {
+            if (x - cx).abs() > inner {
+                plot(x as i32, (cy + dy) as i32, 255);
+            }
+        }
+    }
+}
+
+/// Fills a convex polygon, corners in order either way round.
+pub fn fill_polygon(clip: &Clip, corners: &[(f32, f32)], plot: &mut dyn FnMut(i32, i32, u8)) {
+    if corners.len() < 3 || corners.iter().any(|(x, y)| !(x.is_finite() && y.is_finite())) {
+        return;
+    }
+    let top = corners.iter().fold(f32::INFINITY, |m, c| m.min(c.1));
+    let bottom = corners.iter().fold(f32::NEG_INFINITY, |m, c| m.max(c.1));
+    // rows whose centers are in [top, bottom), columns whose centers are in [left, right)
+    let first_row = ((top - 0.5).ceil() as i64).max(0);
+    let last_row = ((bottom - 0.5).ceil() as i64).min(clip.height as i64);
+    for row in first_row..last_row {
+        let center = row as f32 + 0.5;
+        let mut span = (f32::INFINITY, f32::NEG_INFINITY);
+        for (i, &(xa, ya)) in corners.iter().enumerate() {
+            let (xb, yb) = corners[(i + 1) % corners.len()];
+            // top end first, so an edge shared by two polygons crosses the row at the same x in both
+            let ((xa, ya), (xb, yb)) = if ya <= yb { ((xa, ya), (xb, yb)) } else { ((xb, yb), (xa, ya)) };
+            if ya <= center && center < yb {
+                let x = xa + (center - ya) / (yb - ya) * (xb - xa);
+                span = (span.0.min(x), span.1.max(x));
+            }
+        }
+        let first_column = ((span.0 - 0.5).ceil() as i64).max(0);
+        let last_column = ((span.1 - 0.5).ceil() as i64).min(clip.width as i64);
+        for column in first_column..last_column {
+            plot(column as i32, row as i32, 255);
+        }
+    }
+}
+
+/// Text in the SDL_gfx 8x8 font, top left corner at (x, y).
+pub fn text(clip: &Clip, x: i32, y: i32, text: &str, plot: &mut dyn FnMut(i32, i32, u8)) {
+    for (i, c) in text.chars().enumerate() {
+        let left = x as i64 + i as i64 * GLYPH_SIZE as i64;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for column in 0..GLYPH_SIZE as i64 {
+                if bits & (0x80 >> column) != 0 {
+                    put(clip, plot, left + column, y as i64 + row as i64, 255);
+                }
+            }
+        }
+    }
+}
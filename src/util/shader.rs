@@ -0,0 +1,194 @@
+/*
+----- Shader -----
+what color a surface is at a point. The renderers hand a shader everything they know about
+the point as a Fragment and take back an RGBA color: render_jive_surface draws it as is,
+render_jive_shaded lights it as the base color of the surface's material
+
+    ----- Fragment -----
+    position and normal are in the scene (after the orientation, transformation and
+    placement, before the view), uv are the surface parameters scaled to 0,1 over the
+    sampled range, depth is the projected depth (0 at f_near, 1 at f_far) and surface_id
+    is the JiveSurface's own, for one shader shared by several surfaces
+
+    ----- Combining -----
+    a.mix(b, t) blends two shaders, a.multiply(b) tints one with the other, and a closure
+    |fragment: &Fragment| -> Color is a shader too. Give the result to a surface with
+    JiveSurface::set_shader or the builder
+*/
+
+extern crate sdl2;
+use sdl2::pixels::Color;
+
+use crate::la::linear_algebra::Vec3f;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fragment {
+    pub position: Vec3f,
+    /// unit length, or zero where the surface has no normal
+    pub normal: Vec3f,
+    pub uv: [f32; 2],
+    pub depth: f32,
+    pub surface_id: usize,
+}
+
+pub trait Shader {
+    fn shade(&self, fragment: &Fragment) -> Color;
+
+    /// This shader at t = 0, `other` at t = 1, alpha included.
+    fn mix<S: Shader>(self, other: S, t: f32) -> Mix<Self, S> where Self: Sized {
+        Mix{ a: self, b: other, t }
+    }
+    /// The two colors multiplied channel by channel, white leaves the other as it is.
+    fn multiply<S: Shader>(self, other: S) -> Multiply<Self, S> where Self: Sized {
+        Multiply{ a: self, b: other }
+    }
+}
+
+impl<F: Fn(&Fragment) -> Color> Shader for F {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        self(fragment)
+    }
+}
+
+// -1,1 onto 0,255
+fn signed_channel(x: f32) -> u8 {
+    ((x.clamp(-1.0, 1.0) + 1.0) * 0.5 * 255.0).round() as u8
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let channel = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round().clamp(0.0, 255.0) as u8;
+    Color::RGBA(channel(a.r, b.r), channel(a.g, b.g), channel(a.b, b.b), channel(a.a, b.a))
+}
+
+/// One color everywhere.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Solid(pub Color);
+
+impl Shader for Solid {
+    fn shade(&self, _fragment: &Fragment) -> Color {
+        self.0
+    }
+}
+
+/// The direction from the origin as a color, -1,1 on x, y and z onto 0,255 in r, g and b.
+/// The origin itself has no direction and comes out gray.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Position;
+
+impl Shader for Position {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        let Some(direction) = fragment.position.try_normalize() else {
+            return Color::RGB(128, 128, 128);
+        };
+        Color::RGB(signed_channel(direction.e[0]), signed_channel(direction.e[1]), signed_channel(direction.e[2]))
+    }
+}
+
+/// The normal as a color the way normal maps do it, -1,1 onto 0,255.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Normal;
+
+impl Shader for Normal {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        let n = fragment.normal.e;
+        Color::RGB(signed_channel(n[0]), signed_channel(n[1]), signed_channel(n[2]))
+    }
+}
+
+/// Squares in the surface parameters, `u_squares` across and `v_squares` down.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Checkerboard {
+    pub a: Color,
+    pub b: Color,
+    pub u_squares: u32,
+    pub v_squares: u32,
+}
+
+impl Shader for Checkerboard {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        let u = (fragment.uv[0] * self.u_squares as f32).floor() as i64;
+        let v = (fragment.uv[1] * self.v_squares as f32).floor() as i64;
+        if (u + v).rem_euclid(2) == 0 { self.a } else { self.b }
+    }
+}
+
+/// How far along `axis` a point is, `low` to `high`, through a colormap. Past either end
+/// it keeps the end color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeightMap {
+    // a unit vector, low < high and at least one stop, the constructors see to it
+    axis: Vec3f,
+    low: f32,
+    high: f32,
+    stops: Vec<Color>,
+}
+
+impl HeightMap {
+    /// Viridis, purple through blue and green to yellow, from `low` to `high` along `axis`.
+    pub fn new(axis: Vec3f, low: f32, high: f32) -> Result<HeightMap, String> {
+        HeightMap::with_stops(axis, low, high, vec![Color::RGB(68, 1, 84), Color::RGB(59, 82, 139), Color::RGB(33, 145, 140),
+                                                    Color::RGB(94, 201, 98), Color::RGB(253, 231, 37)])
+    }
+    pub fn with_stops(axis: Vec3f, low: f32, high: f32, stops: Vec<Color>) -> Result<HeightMap, String> {
+        let axis = axis.try_normalize().ok_or(format!("a height map needs an axis, got {:?}", axis.e))?;
+        if !(low.is_finite() && high.is_finite() && low < high) {
+            return Err(format!("a height map needs low < high, got {low} and {high}"));
+        }
+        if stops.is_empty() {
+            return Err("a height map needs at least one color".to_string());
+        }
+        Ok(HeightMap{ axis, low, high, stops })
+    }
+    /// The axis the heights are measured along, made unit length.
+    pub fn axis(&self) -> Vec3f {
+        self.axis
+    }
+    pub fn low(&self) -> f32 {
+        self.low
+    }
+    pub fn high(&self) -> f32 {
+        self.high
+    }
+    /// The colors evenly spaced from low to high.
+    pub fn stops(&self) -> &[Color] {
+        &self.stops
+    }
+}
+
+impl Shader for HeightMap {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        let t = ((fragment.position.dot(&self.axis) - self.low) / (self.high - self.low)).clamp(0.0, 1.0);
+        let last = self.stops.len() - 1;
+        if last == 0 || t.is_nan() {
+            return self.stops[0];
+        }
+        let at = t * last as f32;
+        let i = (at.floor() as usize).min(last - 1);
+        lerp_color(self.stops[i], self.stops[i + 1], at - i as f32)
+    }
+}
+
+pub struct Mix<A, B> {
+    pub a: A,
+    pub b: B,
+    pub t: f32,
+}
+
+impl<A: Shader, B: Shader> Shader for Mix<A, B> {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        lerp_color(self.a.shade(fragment), self.b.shade(fragment), self.t.clamp(0.0, 1.0))
+    }
+}
+
+pub struct Multiply<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Shader, B: Shader> Shader for Multiply<A, B> {
+    fn shade(&self, fragment: &Fragment) -> Color {
+        let (x, y) = (self.a.shade(fragment), self.b.shade(fragment));
+        let channel = |p: u8, q: u8| ((p as u32 * q as u32 + 127) / 255) as u8;
+        Color::RGBA(channel(x.r, y.r), channel(x.g, y.g), channel(x.b, y.b), channel(x.a, y.a))
+    }
+}
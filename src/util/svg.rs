@@ -0,0 +1,145 @@
+/*
+----- SVG -----
+a RenderBackend that keeps the shapes instead of pixels and writes them out as an SVG,
+for figures that stay sharp at any size. Same coordinates as the raster backends: pixel
+(x, y) is the square [x, x + 1) x [y, y + 1), so a line between two pixels runs between
+their centers
+
+    the text is SVG monospace stretched to the 8 pixel advance of the raster font, close
+    but not the same glyphs. Anything that only comes as pixels (plot) is written as 1x1
+    squares
+*/
+
+extern crate sdl2;
+use sdl2::pixels::Color;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::util::backend::{coverage_alpha, RenderBackend};
+use crate::util::font::GLYPH_SIZE;
+
+pub struct SvgDocument {
+    width: u32,
+    height: u32,
+    background: Option<Color>,
+    elements: Vec<String>,
+}
+
+impl SvgDocument {
+    /// An empty, transparent drawing.
+    pub fn new(width: u32, height: u32) -> Result<SvgDocument, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("an svg needs a size, got {width}x{height}"));
+        }
+        Ok(SvgDocument{ width, height, background: None, elements: Vec::new() })
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// The shapes drawn since the last clear, one SVG element each.
+    pub fn elements(&self) -> &[String] {
+        &self.elements
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+                              w = self.width, h = self.height);
+        if let Some(color) = self.background {
+            let _ = writeln!(out, "<rect width=\"100%\" height=\"100%\" {}/>", fill(color));
+        }
+        for element in self.elements.iter() {
+            out.push_str(element);
+            out.push('\n');
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        fs::write(path.as_ref(), self.to_svg()).map_err(|e| format!("could not write {}: {e}", path.as_ref().display()))
+    }
+}
+
+// fill="rgb(..)" and its opacity when it is not opaque
+fn fill(color: Color) -> String {
+    paint("fill", color)
+}
+fn stroke(color: Color) -> String {
+    paint("stroke", color)
+}
+fn paint(what: &str, color: Color) -> String {
+    let mut out = format!("{what}=\"rgb({},{},{})\"", color.r, color.g, color.b);
+    if color.a != 255 {
+        let _ = write!(out, " {what}-opacity=\"{:.3}\"", color.a as f32 / 255.0);
+    }
+    out
+}
+
+// the middle of pixel x, x + 0.5 on either side of 0
+fn pixel_center(x: i32) -> f64 {
+    x as f64 + 0.5
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+impl RenderBackend for SvgDocument {
+    fn size(&self) -> Result<(u32, u32), String> {
+        Ok((self.width, self.height))
+    }
+    fn plot(&mut self, pixels: &[(i32, i32, u8)], color: Color) -> Result<(), String> {
+        for &(x, y, coverage) in pixels.iter() {
+            let color = Color::RGBA(color.r, color.g, color.b, coverage_alpha(color, coverage));
+            self.elements.push(format!("<rect x=\"{x}\" y=\"{y}\" width=\"1\" height=\"1\" {}/>", fill(color)));
+        }
+        Ok(())
+    }
+    /// Everything drawn so far is dropped, the drawing starts again on `color`.
+    fn clear(&mut self, color: Color) -> Result<(), String> {
+        self.elements.clear();
+        self.background = Some(color);
+        Ok(())
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: Color) -> Result<(), String> {
+        self.elements.push(format!("<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" {}/>", fill(color)));
+        Ok(())
+    }
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), thickness: u8, color: Color) -> Result<(), String> {
+        // square caps reach half a pixel past the end centers, like the raster lines
+        self.elements.push(format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\" {}/>",
+            pixel_center(from.0), pixel_center(from.1), pixel_center(to.0), pixel_center(to.1), thickness.max(1), stroke(color)));
+        Ok(())
+    }
+    fn filled_triangle(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32), color: Color) -> Result<(), String> {
+        self.elements.push(format!("<polygon points=\"{},{} {},{} {},{}\" {}/>", a.0, a.1, b.0, b.1, c.0, c.1, fill(color)));
+        Ok(())
+    }
+    fn filled_circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        // the raster disk reaches half a pixel past the radius
+        self.elements.push(format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+                                   pixel_center(center.0), pixel_center(center.1), radius as f64 + 0.5, fill(color)));
+        Ok(())
+    }
+    fn circle(&mut self, center: (i32, i32), radius: i32, color: Color) -> Result<(), String> {
+        self.elements.push(format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke-width=\"1\" {}/>",
+                                   pixel_center(center.0), pixel_center(center.1), radius, stroke(color)));
+        Ok(())
+    }
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color) -> Result<(), String> {
+        let count = text.chars().count();
+        if count == 0 {
+            return Ok(());
+        }
+        // y is the top of the glyphs, svg wants the baseline
+        self.elements.push(format!(
+            "<text x=\"{x}\" y=\"{}\" font-family=\"monospace\" font-size=\"{GLYPH_SIZE}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" {}>{}</text>",
+            y + GLYPH_SIZE - 1, count as i32 * GLYPH_SIZE, fill(color), escape(text)));
+        Ok(())
+    }
+}
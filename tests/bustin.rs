@@ -0,0 +1,131 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivecurve::JiveCurve;
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::{Mat3x3, Mat4x4, Vec3f};
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{project_vertex, render_jive_curve, render_jive_surface, CurveStyle, render_jive_wireframe, ScreenData, WireframeOptions, CAMERA_DISTANCE};
+
+fn screen() -> ScreenData {
+    ScreenData{ screen_width: 800.0, screen_height: 600.0, field_of_view: 90.0, f_near: 1.0, f_far: 11.0,
+                ..ScreenData::default() }
+}
+
+#[test]
+fn project_vertex_matches_a_hand_projection() {
+    let screen_data = screen();
+    let to_clip = screen_data.view_projection();
+    // camera space (0.5, -0.25, 2): a = 0.75, f = 1, w = 2
+    let vertex = Vec3f::from(0.5, -0.25, 2.0 - CAMERA_DISTANCE);
+    let p = project_vertex(&vertex, &Mat3x3::identity(), &to_clip, &screen_data).unwrap();
+    let expected = Vec3f::from((1.0 + 0.1875) * 400.0, (1.0 - 0.125) * 300.0, 0.55);
+    assert!(p.distance(&expected) < 1e-3, "{p:?} != {expected:?}");
+
+    // the transformation is applied first, this one swaps x and y
+    let swap = Mat3x3{ e: [0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0] };
+    let swapped = Vec3f::from(-0.25, 0.5, 2.0 - CAMERA_DISTANCE);
+    let q = project_vertex(&swapped, &swap, &to_clip, &screen_data).unwrap();
+    assert!(q.distance(&p) < 1e-3);
+}
+
+#[test]
+fn project_vertex_rejects_points_behind_the_camera() {
+    let screen_data = screen();
+    let behind = Vec3f::from(0.1, 0.1, -CAMERA_DISTANCE - 1.0);
+    assert!(project_vertex(&behind, &Mat3x3::identity(), &screen_data.view_projection(), &screen_data).is_err());
+}
+
+#[test]
+fn placement_and_camera_are_separate() {
+    // the same point placed 3 to the right and seen from a camera 3 to the right
+    let origin = Vec3f::zero();
+    let mut moved_camera = screen();
+    moved_camera.view = Mat4x4::look_at(&Vec3f::from(3.0, 0.0, -2.0), &Vec3f::from(3.0, 0.0, 0.0), &Vec3f::from(0.0, 1.0, 0.0)).unwrap();
+    let placement = Mat4x4::translation(&Vec3f::from(3.0, 0.0, 0.0));
+    let p = project_vertex(&origin, &Mat3x3::identity(), &(placement * moved_camera.view_projection()), &moved_camera).unwrap();
+
+    let screen_data = screen();
+    let q = project_vertex(&origin, &Mat3x3::identity(), &screen_data.view_projection(), &screen_data).unwrap();
+    assert!(p.distance(&q) < 1e-3, "{p:?} != {q:?}");
+    // dead center
+    assert!((q.e[0] - 400.0).abs() < 1e-3 && (q.e[1] - 300.0).abs() < 1e-3);
+}
+
+// keeps the lines and points it is asked to draw instead of drawing them
+struct LineRecorder {
+    lines: Vec<(Vec3f, Vec3f)>,
+    points: Vec<Vec3f>,
+}
+
+impl RenderBackend for LineRecorder {
+    fn size(&self) -> Result<(u32, u32), String> {
+        Ok((800, 600))
+    }
+    fn plot(&mut self, _pixels: &[(i32, i32, u8)], _color: Color) -> Result<(), String> {
+        Ok(())
+    }
+    fn clear(&mut self, _color: Color) -> Result<(), String> {
+        Ok(())
+    }
+    fn depth_line(&mut self, from: &Vec3f, to: &Vec3f, _thickness: u8, _color: Color) -> Result<(), String> {
+        self.lines.push((*from, *to));
+        Ok(())
+    }
+    fn depth_point(&mut self, at: &Vec3f, _size: u32, _color: Color) -> Result<(), String> {
+        self.points.push(*at);
+        Ok(())
+    }
+}
+
+#[test]
+fn wireframe_draws_every_isoline_segment() {
+    let sphere = JiveSurface::builder(SurfaceKind::sphere(10.0).unwrap()).samples(12, 6).build().unwrap();
+    let options = WireframeOptions{ u_lines: 4, v_lines: 5, ..WireframeOptions::default() };
+    let screen_data = screen();
+    let mut recorder = LineRecorder{ lines: Vec::new(), points: Vec::new() };
+    render_jive_wireframe(&mut recorder, &sphere, &screen_data, &options).unwrap();
+
+    // 4 meridians of 6 segments, and 3 parallels of 12 once the poles are left out
+    assert_eq!(recorder.lines.len(), 4 * 6 + 3 * 12);
+
+    // the segments run along the isolines in order, projected
+    let to_clip = sphere.surface_placement * screen_data.view_projection();
+    let project = |p: &Vec3f| project_vertex(p, &sphere.transformation(), &to_clip, &screen_data).unwrap();
+    let isolines = sphere.isolines(4, 5);
+    let mut segments = recorder.lines.iter();
+    for line in isolines.iter() {
+        for pair in line.windows(2) {
+            let (from, to) = segments.next().unwrap();
+            assert!(from.distance(&project(&pair[0])) < 1e-3 && to.distance(&project(&pair[1])) < 1e-3);
+        }
+    }
+    // a parallel ends where it started
+    let parallel = &recorder.lines[4 * 6..4 * 6 + 12];
+    assert!(parallel[0].0.distance(&parallel[11].1) < 1e-3);
+}
+
+#[test]
+fn surface_too_thin_to_mesh_still_draws_its_points() {
+    // one wrapping u sample is a single meridian, 7 points and no triangles
+    let meridian = JiveSurface::builder(SurfaceKind::sphere(10.0).unwrap()).samples(1, 6).build().unwrap();
+    assert!(meridian.mesh().is_err());
+    let mut recorder = LineRecorder{ lines: Vec::new(), points: Vec::new() };
+    render_jive_surface(&mut recorder, &meridian, &screen()).unwrap();
+    assert_eq!(recorder.points.len(), 7);
+}
+
+#[test]
+fn curve_goes_through_its_orientation_and_placement() {
+    let mut circle = JiveCurve::circle(Vec3f::zero(), Vec3f::from(0.0, 0.0, 1.0), 4.0).unwrap();
+    circle.rotate_pitch(0.7);
+    circle.translate(Vec3f::from(0.25, -0.1, 0.0));
+    let screen_data = screen();
+    let mut recorder = LineRecorder{ lines: Vec::new(), points: Vec::new() };
+    let style = CurveStyle{ depth_bias: 0.0, ..CurveStyle::default() };
+    render_jive_curve(&mut recorder, &circle, &screen_data, &style).unwrap();
+
+    let to_clip = circle.curve_placement * screen_data.view_projection();
+    let first = project_vertex(&circle.polyline()[0], &circle.transformation(), &to_clip, &screen_data).unwrap();
+    assert_eq!(recorder.lines.len(), circle.samples());
+    assert!(recorder.lines[0].0.distance(&first) < 1e-3);
+}
@@ -0,0 +1,175 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{project_vertex, render_jive_surface, ScreenData};
+use jive::util::depth::DepthSorted;
+use jive::util::framebuffer::Framebuffer;
+use jive::util::svg::SvgDocument;
+
+const RED: Color = Color::RGB(255, 0, 0);
+const BLUE: Color = Color::RGB(0, 0, 255);
+
+// a square of two triangles at depth z
+fn square<B: RenderBackend>(backend: &mut B, left: f32, z: f32, color: Color) {
+    let corner = |x: f32, y: f32| Vec3f::from(left + x, 2.0 + y, z);
+    backend.depth_triangle(&corner(0.0, 0.0), &corner(6.0, 0.0), &corner(6.0, 6.0), color).unwrap();
+    backend.depth_triangle(&corner(6.0, 6.0), &corner(0.0, 6.0), &corner(0.0, 0.0), color).unwrap();
+}
+
+#[test]
+fn the_z_buffer_keeps_the_nearest() {
+    // red is nearer, whichever is drawn first
+    for red_first in [true, false] {
+        let mut framebuffer = Framebuffer::with_depth(12, 10).unwrap();
+        if red_first {
+            square(&mut framebuffer, 2.0, 0.2, RED);
+            square(&mut framebuffer, 4.0, 0.6, BLUE);
+        } else {
+            square(&mut framebuffer, 4.0, 0.6, BLUE);
+            square(&mut framebuffer, 2.0, 0.2, RED);
+        }
+        assert_eq!(framebuffer.pixel(6, 4), Some(RED));
+        assert_eq!(framebuffer.pixel(9, 4), Some(BLUE));
+        assert_eq!(framebuffer.depth(6, 4), Some(0.2));
+        assert_eq!(framebuffer.depth(0, 0), Some(f32::INFINITY));
+    }
+
+    // without a z-buffer the last one wins
+    let mut framebuffer = Framebuffer::new(12, 10).unwrap();
+    square(&mut framebuffer, 2.0, 0.2, RED);
+    square(&mut framebuffer, 4.0, 0.6, BLUE);
+    assert_eq!(framebuffer.pixel(6, 4), Some(BLUE));
+    assert_eq!(framebuffer.depth(6, 4), None);
+
+    // clear sends the depth back to far away
+    let mut framebuffer = Framebuffer::with_depth(12, 10).unwrap();
+    square(&mut framebuffer, 2.0, 0.2, RED);
+    framebuffer.clear(Color::RGB(0, 0, 0));
+    assert_eq!(framebuffer.depth(6, 4), Some(f32::INFINITY));
+}
+
+#[test]
+fn depth_is_interpolated_across_triangles_and_lines() {
+    // z = x / 10 across the triangle
+    let mut framebuffer = Framebuffer::with_depth(12, 12).unwrap();
+    let (a, b, c) = (Vec3f::from(0.0, 0.0, 0.0), Vec3f::from(10.0, 0.0, 1.0), Vec3f::from(0.0, 10.0, 0.0));
+    framebuffer.depth_triangle(&a, &b, &c, RED).unwrap();
+    assert!((framebuffer.depth(2, 3).unwrap() - 0.25).abs() < 1e-5);
+
+    // two crossing lines: the nearer one is drawn where they meet
+    let mut framebuffer = Framebuffer::with_depth(12, 12).unwrap();
+    framebuffer.depth_line(&Vec3f::from(0.0, 5.0, 0.3), &Vec3f::from(10.0, 5.0, 0.3), 1, RED).unwrap();
+    framebuffer.depth_line(&Vec3f::from(5.0, 0.0, 0.1), &Vec3f::from(5.0, 10.0, 0.9), 1, BLUE).unwrap();
+    assert_eq!(framebuffer.pixel(5, 2), Some(BLUE));
+    assert_eq!(framebuffer.pixel(5, 8), Some(BLUE));
+    // blue is at 0.1 + 0.8 * 0.5 = 0.5 there, behind red
+    assert_eq!(framebuffer.pixel(5, 5), Some(RED));
+}
+
+#[test]
+fn antialiased_edges_leave_no_depth() {
+    // a near line with a half covered pixel either side of y = 5.5
+    let mut framebuffer = Framebuffer::with_depth(12, 12).unwrap();
+    framebuffer.depth_line(&Vec3f::from(0.0, 5.0, 0.1), &Vec3f::from(10.0, 6.0, 0.1), 1, RED).unwrap();
+    // at x = 5 the line is at y = 5.5, half on row 5 and half on row 6
+    assert_eq!(framebuffer.depth(5, 5), Some(f32::INFINITY));
+    assert_eq!(framebuffer.depth(5, 6), Some(f32::INFINITY));
+    assert_eq!(framebuffer.depth(0, 5), Some(0.1));
+    assert_ne!(framebuffer.pixel(5, 5), Some(Color::RGBA(0, 0, 0, 0)));
+
+    // so a farther surface drawn afterwards still shows through the edge
+    square(&mut framebuffer, 3.0, 0.6, BLUE);
+    let edge = framebuffer.pixel(5, 5).unwrap();
+    assert!(edge.b > 0, "{edge:?}");
+    // but not where the line covered the pixel fully
+    framebuffer.depth_line(&Vec3f::from(3.0, 4.0, 0.1), &Vec3f::from(9.0, 4.0, 0.1), 1, RED).unwrap();
+    square(&mut framebuffer, 3.0, 0.6, BLUE);
+    assert_eq!(framebuffer.pixel(6, 4), Some(RED));
+}
+
+#[test]
+fn painters_order_draws_the_far_side_first() {
+    let mut framebuffer = Framebuffer::new(12, 10).unwrap();
+    let mut sorted = DepthSorted::new(&mut framebuffer);
+    square(&mut sorted, 2.0, 0.2, RED);
+    square(&mut sorted, 4.0, 0.6, BLUE);
+    // nothing is drawn until the frame is flushed
+    sorted.present().unwrap();
+    drop(sorted);
+    assert_eq!(framebuffer.pixel(6, 4), Some(RED));
+    assert_eq!(framebuffer.pixel(9, 4), Some(BLUE));
+
+    // a call without a depth goes on top of what came before it
+    let mut framebuffer = Framebuffer::new(12, 10).unwrap();
+    let mut sorted = DepthSorted::new(&mut framebuffer);
+    square(&mut sorted, 2.0, 0.2, RED);
+    sorted.fill_rect(5, 3, 2, 2, Color::RGB(255, 255, 255)).unwrap();
+    square(&mut sorted, 4.0, 0.6, BLUE);
+    sorted.flush().unwrap();
+    drop(sorted);
+    assert_eq!(framebuffer.pixel(5, 3), Some(BLUE));
+    assert_eq!(framebuffer.pixel(2, 3), Some(RED));
+
+    // clear drops the queue
+    let mut framebuffer = Framebuffer::new(12, 10).unwrap();
+    let mut sorted = DepthSorted::new(&mut framebuffer);
+    square(&mut sorted, 2.0, 0.2, RED);
+    sorted.clear(Color::RGB(0, 0, 0)).unwrap();
+    sorted.present().unwrap();
+    drop(sorted);
+    assert_eq!(framebuffer.pixel(6, 4), Some(Color::RGB(0, 0, 0)));
+
+    // and whatever is still queued when it goes away is drawn then
+    let mut framebuffer = Framebuffer::new(12, 10).unwrap();
+    {
+        let mut sorted = DepthSorted::new(&mut framebuffer);
+        square(&mut sorted, 4.0, 0.6, BLUE);
+        square(&mut sorted, 2.0, 0.2, RED);
+    }
+    assert_eq!(framebuffer.pixel(6, 4), Some(RED));
+    assert_eq!(framebuffer.pixel(9, 4), Some(BLUE));
+}
+
+#[test]
+fn vector_output_comes_out_far_to_near() {
+    let mut svg = SvgDocument::new(12, 10).unwrap();
+    let mut sorted = DepthSorted::new(&mut svg);
+    square(&mut sorted, 2.0, 0.2, RED);
+    square(&mut sorted, 4.0, 0.6, BLUE);
+    sorted.flush().unwrap();
+    drop(sorted);
+    let colors: Vec<bool> = svg.elements().iter().map(|e| e.contains("rgb(255,0,0)")).collect();
+    assert_eq!(colors, vec![false, false, true, true]);
+}
+
+#[test]
+fn a_rotated_sphere_shows_its_near_side() {
+    let screen_data = ScreenData{ screen_width: 200.0, screen_height: 200.0, ..ScreenData::default() };
+    let mut sphere = JiveSurface::new(SurfaceKind::sphere(8.0).unwrap()).unwrap();
+    sphere.rotate_roll(2.5);
+    sphere.rotate_pitch(0.7);
+
+    let mut buffered = Framebuffer::with_depth(200, 200).unwrap();
+    render_jive_surface(&mut buffered, &sphere, &screen_data).unwrap();
+    let mut plain = Framebuffer::new(200, 200).unwrap();
+    let mut sorted = DepthSorted::new(&mut plain);
+    render_jive_surface(&mut sorted, &sphere, &screen_data).unwrap();
+    sorted.present().unwrap();
+    drop(sorted);
+    // the z-buffer and painter's order agree on every pixel
+    assert_eq!(buffered.pixels(), plain.pixels());
+    // which drawing in generation order does not
+    let mut unsorted = Framebuffer::new(200, 200).unwrap();
+    render_jive_surface(&mut unsorted, &sphere, &screen_data).unwrap();
+    assert_ne!(buffered.pixels(), unsorted.pixels());
+
+    // and the point nearest the camera is on top where it lands
+    let to_clip = sphere.surface_placement * screen_data.view_projection();
+    let nearest = sphere.surface_data().iter()
+        .filter_map(|v| project_vertex(v, &sphere.transformation(), &to_clip, &screen_data).ok())
+        .min_by(|p, q| p.e[2].total_cmp(&q.e[2]))
+        .unwrap();
+    assert_eq!(buffered.depth(nearest.e[0] as i32, nearest.e[1] as i32), Some(nearest.e[2]));
+}
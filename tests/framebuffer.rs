@@ -0,0 +1,181 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{render_jive_wireframe, ScreenData, WireframeOptions};
+use jive::util::font::glyph;
+use jive::util::framebuffer::Framebuffer;
+
+const WHITE: Color = Color::RGB(255, 255, 255);
+const BLACK: Color = Color::RGB(0, 0, 0);
+
+fn lit(framebuffer: &Framebuffer) -> Vec<(i32, i32)> {
+    let mut pixels = Vec::new();
+    for y in 0..framebuffer.height() as i32 {
+        for x in 0..framebuffer.width() as i32 {
+            if framebuffer.pixel(x, y) != Some(BLACK) {
+                pixels.push((x, y));
+            }
+        }
+    }
+    pixels
+}
+
+#[test]
+fn framebuffer_basics() {
+    assert!(Framebuffer::new(0, 10).is_err());
+    let mut framebuffer = Framebuffer::new(4, 3).unwrap();
+    assert_eq!(framebuffer.pixel(3, 2), Some(BLACK));
+    assert_eq!(framebuffer.pixel(4, 0), None);
+    assert_eq!(framebuffer.pixel(-1, 0), None);
+
+    framebuffer.clear(Color::RGB(1, 2, 3));
+    assert_eq!(&framebuffer.pixels()[0..4], &[1, 2, 3, 255]);
+    let ppm = framebuffer.to_ppm();
+    assert!(ppm.starts_with(b"P6\n4 3\n255\n"));
+    assert_eq!(ppm.len(), "P6\n4 3\n255\n".len() + 4 * 3 * 3);
+}
+
+#[test]
+fn lines_cover_the_expected_pixels() {
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.line((1, 1), (6, 1), 1, WHITE).unwrap();
+    assert_eq!(lit(&framebuffer), (1..=6).map(|x| (x, 1)).collect::<Vec<_>>());
+
+    // a 45 degree line is fully covered, both ways round
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.line((5, 5), (2, 2), 1, WHITE).unwrap();
+    assert_eq!(lit(&framebuffer), vec![(2, 2), (3, 3), (4, 4), (5, 5)]);
+
+    // halfway between two rows each gets half: 255 * 128 / 255 over black
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.line((0, 0), (4, 2), 1, WHITE).unwrap();
+    assert_eq!(framebuffer.pixel(1, 0), Some(Color::RGBA(128, 128, 128, 255)));
+    assert_eq!(framebuffer.pixel(1, 1), Some(Color::RGBA(128, 128, 128, 255)));
+    assert_eq!(framebuffer.pixel(2, 1), Some(WHITE));
+
+    // 3 wide, centered on the row
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.line((2, 5), (7, 5), 3, WHITE).unwrap();
+    assert_eq!(lit(&framebuffer).len(), 6 * 3);
+    assert_eq!(framebuffer.pixel(2, 4), Some(WHITE));
+    assert_eq!(framebuffer.pixel(7, 6), Some(WHITE));
+}
+
+#[test]
+fn shapes_and_text() {
+    let mut framebuffer = Framebuffer::new(20, 20).unwrap();
+    framebuffer.fill_rect(18, 18, 4, 4, WHITE).unwrap();
+    assert_eq!(lit(&framebuffer), vec![(18, 18), (19, 18), (18, 19), (19, 19)]);
+
+    let mut framebuffer = Framebuffer::new(20, 20).unwrap();
+    framebuffer.filled_circle((10, 10), 2, WHITE).unwrap();
+    // rows of 3, 5, 5, 5, 3
+    assert_eq!(lit(&framebuffer).len(), 21);
+    framebuffer.circle((10, 10), 4, Color::RGB(255, 0, 0)).unwrap();
+    assert_eq!(framebuffer.pixel(10, 6), Some(Color::RGB(255, 0, 0)));
+    assert_eq!(framebuffer.pixel(10, 8), Some(WHITE));
+
+    let mut framebuffer = Framebuffer::new(20, 20).unwrap();
+    framebuffer.text(3, 4, "A", WHITE).unwrap();
+    let rows = glyph('A');
+    for (y, bits) in rows.iter().enumerate() {
+        for x in 0..8 {
+            let on = bits & (0x80 >> x) != 0;
+            assert_eq!(framebuffer.pixel(3 + x, 4 + y as i32) == Some(WHITE), on);
+        }
+    }
+    assert_eq!(glyph('é'), glyph('?'));
+}
+
+#[test]
+fn blending_matches_sdl() {
+    let mut framebuffer = Framebuffer::new(2, 1).unwrap();
+    framebuffer.clear(Color::RGB(200, 100, 0));
+    framebuffer.fill_rect(0, 0, 1, 1, Color::RGBA(0, 0, 255, 64)).unwrap();
+    // 200 * 191 / 255, 100 * 191 / 255, 255 * 64 / 255, 255 * 191 / 255 + 64
+    assert_eq!(framebuffer.pixel(0, 0), Some(Color::RGBA(149, 74, 64, 255)));
+    assert_eq!(framebuffer.pixel(1, 0), Some(Color::RGB(200, 100, 0)));
+}
+
+#[test]
+fn far_off_coordinates_are_clipped() {
+    let mut framebuffer = Framebuffer::new(16, 8).unwrap();
+    framebuffer.line((i32::MIN, 3), (i32::MAX, 3), 1, WHITE).unwrap();
+    framebuffer.line((-2_000_000_000, -5), (2_000_000_000, -5), 4, WHITE).unwrap();
+    framebuffer.filled_circle((8, 4), i32::MAX / 2, Color::RGB(1, 1, 1)).unwrap();
+    assert_eq!(framebuffer.pixel(0, 3), Some(Color::RGB(1, 1, 1)));
+}
+
+#[test]
+fn scenes_render_without_a_window() {
+    let screen_data = ScreenData{ screen_width: 160.0, screen_height: 120.0, ..ScreenData::default() };
+    let mut sphere = JiveSurface::new(SurfaceKind::sphere(8.0).unwrap()).unwrap();
+    sphere.rotate_roll(0.4);
+    let draw = |sphere: &JiveSurface| {
+        let mut framebuffer = Framebuffer::new(160, 120).unwrap();
+        render_jive_wireframe(&mut framebuffer, sphere, &screen_data, &WireframeOptions::default()).unwrap();
+        framebuffer
+    };
+    let first = draw(&sphere);
+    assert!(lit(&first).len() > 500);
+    // the sphere is centered, so is its picture
+    let (sx, sy) = lit(&first).iter().fold((0i64, 0i64), |s, p| (s.0 + p.0 as i64, s.1 + p.1 as i64));
+    let count = lit(&first).len() as i64;
+    assert!((sx / count - 80).abs() <= 2 && (sy / count - 60).abs() <= 2);
+    assert_eq!(first.pixels(), draw(&sphere).pixels());
+}
+
+#[test]
+fn points_and_triangles() {
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.point(3, 4, WHITE).unwrap();
+    framebuffer.point(10, 4, WHITE).unwrap();
+    assert_eq!(lit(&framebuffer), vec![(3, 4)]);
+
+    // the pixel centers inside a right triangle with legs of 4, the ones on the long side left out
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    framebuffer.filled_triangle((0.0, 0.0), (4.0, 0.0), (0.0, 4.0), WHITE).unwrap();
+    assert_eq!(lit(&framebuffer).len(), 3 + 2 + 1);
+
+    // two triangles sharing a diagonal cover the square once, nothing twice
+    let mut framebuffer = Framebuffer::new(10, 10).unwrap();
+    let half = Color::RGBA(255, 255, 255, 128);
+    let corners = [(1.3, 1.1), (8.7, 1.6), (8.2, 8.9), (1.1, 7.4)];
+    framebuffer.filled_triangle(corners[0], corners[1], corners[2], half).unwrap();
+    framebuffer.filled_triangle(corners[2], corners[3], corners[0], half).unwrap();
+    let mut whole = Framebuffer::new(10, 10).unwrap();
+    whole.plot(&filled_quad(&corners), half).unwrap();
+    assert_eq!(framebuffer.pixels(), whole.pixels());
+}
+
+// the quad filled in one go, to compare the two triangles with
+fn filled_quad(corners: &[(f32, f32)]) -> Vec<(i32, i32, u8)> {
+    let mut pixels = Vec::new();
+    jive::util::raster::fill_polygon(&jive::util::raster::Clip{ width: 10, height: 10 }, corners,
+                                     &mut |x, y, coverage| pixels.push((x, y, coverage)));
+    pixels
+}
+
+#[test]
+fn clear_and_present_through_the_trait() {
+    fn frame<B: RenderBackend>(backend: &mut B) -> Result<(), String> {
+        backend.clear(Color::RGB(9, 9, 9))?;
+        backend.point(0, 0, WHITE)?;
+        backend.present()
+    }
+    let mut framebuffer = Framebuffer::new(3, 3).unwrap();
+    frame(&mut framebuffer).unwrap();
+    assert_eq!(framebuffer.pixel(0, 0), Some(WHITE));
+    assert_eq!(framebuffer.pixel(2, 2), Some(Color::RGB(9, 9, 9)));
+}
+
+#[test]
+fn points_left_of_zero_land_in_negative_pixels() {
+    // (-0.5, -0.5) is in pixel (-1, -1), so a 2 x 2 point only reaches pixel (0, 0)
+    for mut framebuffer in [Framebuffer::new(4, 4).unwrap(), Framebuffer::with_depth(4, 4).unwrap()] {
+        framebuffer.depth_point(&Vec3f::from(-0.5, -0.5, 0.5), 2, WHITE).unwrap();
+        assert_eq!(lit(&framebuffer), vec![(0, 0)]);
+    }
+}
@@ -0,0 +1,98 @@
+use jive::jives::jiveconic::Conic2D;
+use jive::jives::jivesection::{ConicClass, ConicSection};
+use jive::jives::jivesurface::SurfaceKind;
+
+fn close(p: [f32; 2], q: [f32; 2]) -> bool {
+    (p[0] - q[0]).abs() < 1e-3 && (p[1] - q[1]).abs() < 1e-3
+}
+
+fn has(points: &[[f32; 2]], q: [f32; 2]) -> bool {
+    points.iter().any(|&p| close(p, q))
+}
+
+// x'^2/a2 + y'^2/b2 (signs included) = 1 in a frame turned by angle and moved to center
+fn rotated(a2: f32, b2: f32, angle: f32, center: [f32; 2]) -> [f32; 6] {
+    let (s, c) = angle.sin_cos();
+    let (p, q) = (1.0 / a2, 1.0 / b2);
+    let (a, b, cc) = (p * c * c + q * s * s, 2.0 * (p - q) * s * c, p * s * s + q * c * c);
+    let [h, k] = center;
+    [a, b, cc, -2.0 * a * h - b * k, -b * h - 2.0 * cc * k, a * h * h + b * h * k + cc * k * k - 1.0]
+}
+
+#[test]
+fn rotated_ellipse_properties() {
+    let center = [1.0, 2.0];
+    let angle = 30.0f32.to_radians();
+    let conic = Conic2D::new(rotated(9.0, 4.0, angle, center)).unwrap();
+    assert_eq!(conic.class, ConicClass::Ellipse);
+    assert!(close(conic.center.unwrap(), center));
+    assert!((conic.eccentricity.unwrap() - 5.0f32.sqrt() / 3.0).abs() < 1e-4);
+
+    let (s, c) = angle.sin_cos();
+    let focal = 5.0f32.sqrt();
+    assert!(has(&conic.vertices, [1.0 + 3.0 * c, 2.0 + 3.0 * s]));
+    assert!(has(&conic.foci, [1.0 - focal * c, 2.0 - focal * s]));
+    for v in conic.vertices.iter() {
+        assert!(conic.evaluate(v[0], v[1]).abs() < 1e-4);
+    }
+    // directrices sit a / e = 9 / sqrt(5) from the center
+    let d = conic.directrices[0].point;
+    assert!((((d[0] - 1.0).powi(2) + (d[1] - 2.0).powi(2)).sqrt() - 9.0 / focal).abs() < 1e-3);
+    for polyline in conic.polylines(10.0, 90) {
+        assert!(polyline.iter().all(|p| conic.evaluate(p[0], p[1]).abs() < 1e-3));
+    }
+}
+
+#[test]
+fn hyperbola_and_parabola_properties() {
+    // x^2/4 - y^2 = 1
+    let hyperbola = Conic2D::new([0.25, 0.0, -1.0, 0.0, 0.0, -1.0]).unwrap();
+    assert_eq!(hyperbola.class, ConicClass::Hyperbola);
+    assert!(has(&hyperbola.vertices, [2.0, 0.0]) && has(&hyperbola.vertices, [-2.0, 0.0]));
+    assert!(has(&hyperbola.foci, [5.0f32.sqrt(), 0.0]));
+    assert!((hyperbola.eccentricity.unwrap() - 5.0f32.sqrt() / 2.0).abs() < 1e-5);
+    for asymptote in hyperbola.asymptotes.iter() {
+        let slope = asymptote.direction[1] / asymptote.direction[0];
+        assert!((slope.abs() - 0.5).abs() < 1e-5);
+    }
+    assert_eq!(hyperbola.polylines(10.0, 40).len(), 2);
+
+    // y = x^2 - 2x + 3, vertex (1, 2), focus a quarter above
+    let parabola = Conic2D::new([1.0, 0.0, 0.0, -2.0, -1.0, 3.0]).unwrap();
+    assert_eq!(parabola.class, ConicClass::Parabola);
+    assert!(close(parabola.vertices[0], [1.0, 2.0]));
+    assert!(close(parabola.foci[0], [1.0, 2.25]));
+    assert!((parabola.directrices[0].point[1] - 1.75).abs() < 1e-4);
+    assert!(parabola.directrices[0].direction[1].abs() < 1e-6);
+    assert_eq!(parabola.eccentricity, Some(1.0));
+    for p in parabola.polylines(10.0, 40)[0].iter() {
+        assert!(parabola.evaluate(p[0], p[1]).abs() < 1e-2 * (1.0 + p[1].abs()));
+    }
+}
+
+#[test]
+fn degenerate_conics() {
+    let table = [
+        ([1.0, 0.0, -1.0, 0.0, 0.0, 0.0], ConicClass::LinePair),
+        ([1.0, 0.0, 1.0, 0.0, 0.0, 0.0], ConicClass::Point),
+        ([1.0, 0.0, 1.0, 0.0, 0.0, 1.0], ConicClass::Empty),
+        ([1.0, 0.0, 0.0, 0.0, 0.0, -4.0], ConicClass::ParallelLines),
+        ([1.0, -2.0, 1.0, 0.0, 0.0, 0.0], ConicClass::Line),
+    ];
+    for (coefficients, class) in table {
+        assert_eq!(Conic2D::new(coefficients).unwrap().class, class, "{coefficients:?}");
+    }
+    assert!(Conic2D::new([0.0, 0.0, 0.0, 1.0, 1.0, 1.0]).is_err());
+}
+
+#[test]
+fn agrees_with_the_cone_section() {
+    let cone = SurfaceKind::cone(2.0, 5.0).unwrap();
+    for (plane, class) in [([0.3, 0.2, 1.0, 4.0], ConicClass::Ellipse), ([1.0, 0.0, 0.0, 2.0], ConicClass::Hyperbola)] {
+        let plane = SurfaceKind::plane(plane[0], plane[1], plane[2], plane[3]).unwrap();
+        let section = ConicSection::new(&cone, &plane).unwrap();
+        let conic = Conic2D::new(section.coefficients).unwrap();
+        assert_eq!(conic.class, class);
+        assert!((conic.eccentricity.unwrap() - section.eccentricity()).abs() < 1e-3);
+    }
+}
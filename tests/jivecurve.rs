@@ -0,0 +1,77 @@
+use jive::jives::jivecurve::JiveCurve;
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+
+#[test]
+fn curve_rejects_bad_parameters() {
+    assert!(JiveCurve::new(|t| Vec3f::from(t, 0.0, 0.0), (1.0, 0.0), 10).is_err());
+    assert!(JiveCurve::new(|t| Vec3f::from(t, 0.0, 0.0), (0.0, 1.0), 0).is_err());
+    assert!(JiveCurve::circle(Vec3f::from(0.0, 0.0, 0.0), Vec3f::from(0.0, 0.0, 0.0), 1.0).is_err());
+    assert!(JiveCurve::helix(-1.0, 1.0, 2.0).is_err());
+    assert!(JiveCurve::conic(-0.5, 1.0).is_err());
+}
+
+#[test]
+fn open_and_closed_sampling() {
+    let line = JiveCurve::line(Vec3f::from(-10.0, 0.0, 0.0), Vec3f::from(10.0, 0.0, 0.0)).unwrap();
+    let points = line.polyline();
+    assert_eq!(points.len(), 2);
+    assert!((points[0].e[0] + 0.5).abs() < 1e-6 && (points[1].e[0] - 0.5).abs() < 1e-6);
+
+    let circle = JiveCurve::circle(Vec3f::from(1.0, 2.0, 3.0), Vec3f::from(1.0, 1.0, 0.0), 4.0).unwrap();
+    assert_eq!(circle.points().len(), circle.samples());
+    let polyline = circle.polyline();
+    assert_eq!(polyline.first().unwrap().e, polyline.last().unwrap().e);
+    for p in circle.points() {
+        let d = [p.e[0] * 20.0 - 1.0, p.e[1] * 20.0 - 2.0, p.e[2] * 20.0 - 3.0];
+        assert!(((d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt() - 4.0).abs() < 1e-4);
+        // in the plane through the center perpendicular to (1, 1, 0)
+        assert!((d[0] + d[1]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn helix_rises_one_pitch_per_turn() {
+    let helix = JiveCurve::helix(3.0, 2.0, 4.0).unwrap();
+    let points = helix.points();
+    assert_eq!(points.len(), 361);
+    let last = points.last().unwrap();
+    assert!((last.e[0] * 20.0 - 3.0).abs() < 1e-3 && (last.e[2] * 20.0 - 8.0).abs() < 1e-3);
+}
+
+#[test]
+fn conic_points_keep_the_focus_directrix_ratio() {
+    for e in [0.0, 0.5, 1.0, 2.5] {
+        let conic = JiveCurve::conic(e, 3.0).unwrap();
+        assert_eq!(conic.is_closed(), e < 1.0);
+        for p in conic.points() {
+            let (x, y) = (p.e[0] * 20.0, p.e[1] * 20.0);
+            // focus at the origin, directrix x = p / e
+            let to_focus = (x * x + y * y).sqrt();
+            assert!(to_focus <= 20.0 + 1e-3);
+            if e > 0.0 {
+                assert!((to_focus - e * (3.0 / e - x)).abs() < 1e-3 * (1.0 + to_focus), "e = {e} at ({x}, {y})");
+            } else {
+                assert!((to_focus - 3.0).abs() < 1e-4);
+            }
+        }
+    }
+}
+
+#[test]
+fn curve_turns_and_moves_with_a_surface() {
+    let mut curve = JiveCurve::helix(5.0, 2.0, 3.0).unwrap();
+    let mut surface = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap();
+    for _ in 0..1000 {
+        curve.rotate_roll(0.01);
+        surface.rotate_roll(0.01);
+        curve.rotate_yaw(-0.02);
+        surface.rotate_yaw(-0.02);
+    }
+    let (c, s) = (curve.transformation().e, surface.transformation().e);
+    assert!(c.iter().zip(s.iter()).all(|(c, s)| (c - s).abs() < 1e-5));
+
+    curve.translate(Vec3f::from(0.5, 0.0, -0.5));
+    surface.translate(Vec3f::from(0.5, 0.0, -0.5));
+    assert_eq!(curve.curve_placement, surface.surface_placement);
+}
@@ -0,0 +1,58 @@
+use jive::jives::jiveconic::Conic2D;
+use jive::jives::jivedandelin::{dandelin_spheres, DandelinScene};
+use jive::jives::jivesurface::SurfaceKind;
+
+fn plane(p: [f32; 4]) -> SurfaceKind {
+    SurfaceKind::plane(p[0], p[1], p[2], p[3]).unwrap()
+}
+
+#[test]
+fn spheres_touch_cone_and_plane() {
+    let steepness = 2.0;
+    let sin = 1.0 / (1.0f32 + steepness * steepness).sqrt();
+    // ellipse, hyperbola, parabola, circle
+    let cases = [([0.5, 0.0, 1.0, 4.0], 2), ([1.0, 0.0, 0.2, 2.0], 2), ([2.0, 0.0, -1.0, 3.0], 1), ([0.0, 0.0, 1.0, 6.0], 2)];
+    for (p, count) in cases {
+        let spheres = dandelin_spheres(steepness, &plane(p)).unwrap();
+        assert_eq!(spheres.len(), count, "{p:?}");
+        let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        for sphere in spheres.iter() {
+            let [x, y, z] = sphere.center.e;
+            assert!(x == 0.0 && y == 0.0);
+            // distance to the cone and to the plane are both the radius
+            assert!((z.abs() * sin - sphere.radius).abs() < 1e-4);
+            let to_plane = (p[0] * x + p[1] * y + p[2] * z - p[3]).abs() / length;
+            assert!((to_plane - sphere.radius).abs() < 1e-4 * (1.0 + sphere.radius), "{p:?}");
+            // the focus is on the plane and on the sphere
+            let [fx, fy, fz] = sphere.focus.e;
+            assert!((p[0] * fx + p[1] * fy + p[2] * fz - p[3]).abs() < 1e-3);
+            let to_center = (fx * fx + fy * fy + (fz - z) * (fz - z)).sqrt();
+            assert!((to_center - sphere.radius).abs() < 1e-3 * (1.0 + sphere.radius));
+            // the tangency circle lies on the cone
+            assert!((sphere.tangency_height.abs() - steepness * sphere.tangency_radius).abs() < 1e-4 * (1.0 + sphere.radius));
+        }
+    }
+    // a hyperbola has one sphere on each nappe, an ellipse both on the same one
+    let hyperbola = dandelin_spheres(steepness, &plane([1.0, 0.0, 0.2, 2.0])).unwrap();
+    assert!(hyperbola[0].center.e[2] * hyperbola[1].center.e[2] < 0.0);
+    let ellipse = dandelin_spheres(steepness, &plane([0.5, 0.0, 1.0, 4.0])).unwrap();
+    assert!(ellipse[0].center.e[2] * ellipse[1].center.e[2] > 0.0);
+    assert!(dandelin_spheres(steepness, &plane([1.0, 1.0, 0.0, 0.0])).unwrap().is_empty());
+}
+
+#[test]
+fn spheres_touch_the_plane_at_the_foci() {
+    let cone = SurfaceKind::cone(2.0, 9.0).unwrap();
+    for p in [[0.5, 0.0, 1.0, 4.0], [1.0, -0.3, 0.2, 2.0], [0.2, 0.4, 1.0, 5.0]] {
+        let scene = DandelinScene::new(&cone, &plane(p)).unwrap();
+        assert_eq!(scene.sphere_surfaces.len(), scene.spheres.len());
+        assert_eq!(scene.tangency_circles.len(), scene.spheres.len());
+        let conic = Conic2D::new(scene.section.coefficients).unwrap();
+        for focus in conic.foci.iter() {
+            let world = scene.section.to_world(focus[0], focus[1]);
+            assert!(scene.spheres.iter().any(|sphere| {
+                (0..3).all(|k| (sphere.focus.e[k] - world.e[k]).abs() < 1e-2)
+            }), "{p:?} focus {world:?}");
+        }
+    }
+}
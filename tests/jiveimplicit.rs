@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use jive::jives::jiveimplicit::{polygonize, Bounds};
+use jive::jives::jivequadric::Quadric;
+use jive::jives::jivesurface::JiveSurface;
+use jive::la::linear_algebra::Vec3f;
+
+// the 1/20 render scale every surface comes out in
+const SCALE: f32 = 1.0 / 20.0;
+
+#[test]
+fn implicit_sphere_is_closed_and_outward() {
+    let bounds = Bounds::cube(1.5).unwrap();
+    let mesh = polygonize(|x, y, z| x * x + y * y + z * z - 1.0, &bounds, 16).unwrap();
+    assert!(mesh.triangle_count() > 0);
+
+    for (p, n) in mesh.positions.iter().zip(mesh.normals.iter()) {
+        assert!((p.magnitude() - SCALE).abs() < 0.05 * SCALE, "{:?}", p);
+        let d = p.e[0] * n.e[0] + p.e[1] * n.e[1] + p.e[2] * n.e[2];
+        assert!(d > 0.9 * SCALE, "{:?} {:?}", p, n);
+    }
+
+    // shared vertices and consistent faces: every edge borders two triangles
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for tri in mesh.indices.iter() {
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+        // winding follows the outward normal
+        let [a, b, c] = tri.map(|i| mesh.positions[i].e);
+        let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+        let face = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        assert!(face[0] * a[0] + face[1] * a[1] + face[2] * a[2] > 0.0);
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+fn implicit_surface_renders_like_any_other() {
+    let bounds = Bounds::cube(1.5).unwrap();
+    let surface = JiveSurface::implicit(|x, y, z| x.powi(4) + y.powi(4) + z.powi(4) - x * y * z - 1.0, &bounds, 12).unwrap();
+    assert!(!surface.surface_data().is_empty());
+    assert_eq!(surface.surface_data().len(), surface.mesh().unwrap().vertex_count());
+    assert!(!surface.isolines(8, 8).is_empty());
+
+    assert!(Bounds::new([0.0, 0.0, 0.0], [1.0, -1.0, 1.0]).is_err());
+    assert!(polygonize(|x, _, _| x, &bounds, 0).is_err());
+}
+
+#[test]
+fn implicit_surfaces_share_the_quadric_scale() {
+    // x^2 + y^2 + z^2 = 25 both ways, radius 5 in the -10,10 space
+    let implicit = JiveSurface::implicit(|x, y, z| x * x + y * y + z * z - 25.0, &Bounds::cube(6.0).unwrap(), 24).unwrap();
+    let quadric = Quadric::new([1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -25.0]).unwrap().to_surface().unwrap();
+    let radius = |points: Vec<Vec3f>| {
+        points.iter().map(|p| p.magnitude()).sum::<f32>() / points.len() as f32
+    };
+    let (implicit_radius, quadric_radius) = (radius(implicit.surface_data()), radius(quadric.surface_data()));
+    assert!((quadric_radius - 5.0 * SCALE).abs() < 1e-5);
+    assert!((implicit_radius - quadric_radius).abs() < 0.01 * quadric_radius, "{implicit_radius} vs {quadric_radius}");
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+
+#[test]
+fn sphere_mesh_is_closed() {
+    let sphere = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap();
+    let mesh = sphere.mesh().unwrap();
+
+    // 0..=180 by 30 is 7 rows, the two poles collapse, 360 / 4 = 90 columns
+    assert_eq!(mesh.vertex_count(), 2 + 5 * 90);
+    assert_eq!(mesh.normals.len(), mesh.vertex_count());
+    assert_eq!(mesh.uvs.len(), mesh.vertex_count());
+
+    // every edge of a closed surface borders exactly two triangles
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for tri in mesh.indices.iter() {
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+fn plane_mesh_does_not_wrap() {
+    let plane = JiveSurface::new(SurfaceKind::plane(0.0, 0.0, 1.0, 2.0).unwrap()).unwrap();
+    let mesh = plane.mesh().unwrap();
+    // 11 x 11 samples, 10 x 10 quads
+    assert_eq!(mesh.vertex_count(), 121);
+    assert_eq!(mesh.triangle_count(), 200);
+}
+
+#[test]
+fn custom_surface_wraps_both_ways() {
+    use std::f32::consts::PI;
+    use jive::jives::jivemesh::Mesh;
+    use jive::jives::jiveparametric::{FnSurface, Sampling};
+    use jive::la::linear_algebra::Vec3f;
+
+    let torus = FnSurface::new(|u: f32, v: f32| {
+        let r = 3.0 + v.cos();
+        Vec3f::from(r * u.cos(), r * u.sin(), v.sin())
+    }, (0.0, 2.0 * PI), (0.0, 2.0 * PI)).periodic(true, true);
+    let mesh = Mesh::from_parametric(&torus, &Sampling::new(&torus, 20, 10)).unwrap();
+
+    // no seams and no poles: one vertex and two triangles per grid cell
+    assert_eq!(mesh.vertex_count(), 200);
+    assert_eq!(mesh.triangle_count(), 400);
+}
+
+#[test]
+fn torus_mesh_wraps_both_ways() {
+    let torus = JiveSurface::new(SurfaceKind::torus(6.0, 2.0).unwrap()).unwrap();
+    let mesh = torus.mesh().unwrap();
+    assert_eq!(mesh.vertex_count(), 72 * 36);
+    assert_eq!(mesh.triangle_count(), 2 * 72 * 36);
+}
+
+#[test]
+fn sphere_triangles_face_outward() {
+    let mesh = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap().mesh().unwrap();
+    for tri in mesh.indices.iter() {
+        let [p0, p1, p2] = tri.map(|k| mesh.positions[k].e);
+        let a = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+        let b = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+        let face = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+        let centroid = [p0[0] + p1[0] + p2[0], p0[1] + p1[1] + p2[1], p0[2] + p1[2] + p2[2]];
+        assert!(face[0] * centroid[0] + face[1] * centroid[1] + face[2] * centroid[2] > 0.0);
+    }
+    // the vertex normals are the sphere's own, the poles included
+    for (p, n) in mesh.positions.iter().zip(mesh.normals.iter()) {
+        let radial = p.normalize();
+        for k in 0..3 {
+            assert!((radial.e[k] - n.e[k]).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn double_cone_apex_is_one_vertex() {
+    let cone = JiveSurface::builder(SurfaceKind::elliptic_cone(1.0, 0.5, 8.0).unwrap()).samples(16, 8).build().unwrap();
+    let mesh = cone.mesh().unwrap();
+    // v = -8..8 in 8 steps puts the apex on the middle row, which shrinks to one vertex
+    assert_eq!(mesh.vertex_count(), 8 * 16 + 1);
+    assert_eq!(mesh.positions.iter().filter(|p| p.magnitude() < 1e-6).count(), 1);
+    // a fan on either side of it and no triangle folded flat
+    assert_eq!(mesh.triangle_count(), 2 * 16 * 8 - 2 * 16);
+    for tri in mesh.indices.iter() {
+        let [p0, p1, p2] = tri.map(|k| mesh.positions[k]);
+        assert!((p1 - p0).cross(&(p2 - p0)).magnitude() > 1e-8, "{tri:?}");
+    }
+}
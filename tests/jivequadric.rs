@@ -0,0 +1,91 @@
+use jive::jives::jivequadric::{Quadric, QuadricClass};
+use jive::la::linear_algebra::Mat3x3;
+
+fn quadric(coefficients: [f32; 10]) -> Quadric {
+    Quadric::new(coefficients).unwrap()
+}
+
+// value of the equation at a rendered point, undoing the 1/20 render scale
+fn residual(q: &Quadric, p: [f32; 3]) -> f32 {
+    let [x, y, z] = [p[0] * 20.0, p[1] * 20.0, p[2] * 20.0];
+    let [a, b, c, d, e, f, g, h, i, j] = q.coefficients;
+    a*x*x + b*y*y + c*z*z + d*x*y + e*y*z + f*x*z + g*x + h*y + i*z + j
+}
+
+#[test]
+fn classifies_canonical_quadrics() {
+    let cases = [
+        ([1.0, 2.0, 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -4.0], QuadricClass::Ellipsoid),
+        ([1.0, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0], QuadricClass::HyperboloidOneSheet),
+        ([1.0, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0], QuadricClass::HyperboloidTwoSheets),
+        ([1.0, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], QuadricClass::EllipticCone),
+        ([1.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0], QuadricClass::EllipticParaboloid),
+        ([1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0], QuadricClass::HyperbolicParaboloid),
+        ([1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -4.0], QuadricClass::EllipticCylinder),
+        ([1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -4.0], QuadricClass::HyperbolicCylinder),
+        ([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0], QuadricClass::ParabolicCylinder),
+        ([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, -3.0], QuadricClass::Plane),
+        ([1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], QuadricClass::IntersectingPlanes),
+        ([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -4.0], QuadricClass::ParallelPlanes),
+        ([1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], QuadricClass::CoincidentPlanes),
+        ([1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], QuadricClass::Line),
+        ([1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], QuadricClass::Point),
+        ([1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0], QuadricClass::Empty),
+    ];
+    for (coefficients, class) in cases {
+        assert_eq!(quadric(coefficients).classify(), class, "{:?}", coefficients);
+    }
+    assert!(Quadric::new([0.0; 10]).is_err());
+}
+
+#[test]
+fn rotated_and_translated_ellipsoid() {
+    // x'^2 / 9 + y'^2 / 4 + z^2 = 1 rotated 45 degrees about z and centered on (1, 2, -1):
+    // expanded by hand into the general form
+    let q = quadric([0.1805556, 0.1805556, 1.0, -0.1388889, 0.0, 0.0,
+                     -0.0833333, -0.5833333, 2.0, 0.625]);
+    let (class, principal) = q.principal_axes();
+    assert_eq!(class, QuadricClass::Ellipsoid);
+    for (c, expected) in principal.center.e.iter().zip([1.0, 2.0, -1.0]) {
+        assert!((c - expected).abs() < 1e-3, "{:?}", principal.center);
+    }
+    let mut semi_axes: Vec<f32> = principal.squares.iter().map(|s| (-principal.constant / s).sqrt()).collect();
+    semi_axes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for (axis, expected) in semi_axes.iter().zip([1.0, 2.0, 3.0]) {
+        assert!((axis - expected).abs() < 1e-3, "{:?}", semi_axes);
+    }
+
+    let surface = q.to_surface().unwrap();
+    for p in surface.surface_data() {
+        assert!(residual(&q, p.e).abs() < 1e-3, "{:?}", p);
+    }
+}
+
+#[test]
+fn two_sheets_are_two_patches() {
+    let q = quadric([-1.0, -1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0]);
+    let surface = q.to_surface().unwrap();
+    assert_eq!(surface.patches().count(), 2);
+    let points = surface.surface_data();
+    assert!(points.iter().any(|p| p.e[2] > 0.0) && points.iter().any(|p| p.e[2] < 0.0));
+    for p in points {
+        assert!(residual(&q, p.e).abs() < 1e-2 * (1.0 + p.e[2].abs() * 400.0), "{:?}", p);
+    }
+    assert!(quadric([1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]).to_surface().is_err());
+}
+
+#[test]
+fn symmetric_eigen_reconstructs_matrix() {
+    let m = Mat3x3{ e: [4.0, 1.0, -2.0,
+                        1.0, 2.0, 0.5,
+                        -2.0, 0.5, 3.0] };
+    let (values, vectors) = m.symmetric_eigen();
+    for row in 0..3 {
+        for col in 0..3 {
+            let sum: f32 = values.iter().enumerate()
+                .map(|(k, value)| vectors.e[row * 3 + k] * value * vectors.e[col * 3 + k])
+                .sum();
+            assert!((sum - m.e[row * 3 + col]).abs() < 1e-5);
+        }
+    }
+}
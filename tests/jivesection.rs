@@ -0,0 +1,61 @@
+use jive::jives::jivesection::{ConicClass, ConicSection};
+use jive::jives::jivesurface::SurfaceKind;
+
+fn section(steepness: f32, plane: [f32; 4]) -> ConicSection {
+    let cone = SurfaceKind::cone(steepness, 5.0).unwrap();
+    let plane = SurfaceKind::plane(plane[0], plane[1], plane[2], plane[3]).unwrap();
+    ConicSection::new(&cone, &plane).unwrap()
+}
+
+#[test]
+fn sections_are_classified() {
+    let table = [
+        ([0.0, 0.0, 1.0, 10.0], ConicClass::Ellipse),
+        ([0.3, 0.2, 1.0, 4.0], ConicClass::Ellipse),
+        // parallel to the generator along (1, 0, 2)
+        ([2.0, 0.0, -1.0, 3.0], ConicClass::Parabola),
+        ([1.0, 0.0, 0.0, 2.0], ConicClass::Hyperbola),
+        ([0.0, 0.0, 1.0, 0.0], ConicClass::Point),
+        ([2.0, 0.0, -1.0, 0.0], ConicClass::Line),
+        ([1.0, 1.0, 0.0, 0.0], ConicClass::LinePair),
+    ];
+    for (plane, class) in table {
+        assert_eq!(section(2.0, plane).class, class, "{plane:?}");
+    }
+    let circle = section(2.0, [0.0, 0.0, 1.0, 10.0]);
+    assert!(circle.eccentricity().abs() < 1e-6);
+    assert_eq!(circle.curves().unwrap().len(), 1);
+    assert!(circle.curves().unwrap()[0].is_closed());
+    assert_eq!(section(2.0, [1.0, 0.0, 0.0, 2.0]).curves().unwrap().len(), 2);
+    assert_eq!(section(2.0, [1.0, 1.0, 0.0, 0.0]).curves().unwrap().len(), 2);
+
+    let cone = SurfaceKind::cone(2.0, 5.0).unwrap();
+    assert!(ConicSection::new(&cone, &cone).is_err());
+}
+
+#[test]
+fn section_points_lie_on_cone_and_plane() {
+    let planes = [[0.3, 0.2, 1.0, 4.0], [2.0, 0.0, -1.0, 3.0], [1.0, -0.5, 0.25, 2.0], [0.0, 0.0, 1.0, 10.0]];
+    for plane in planes {
+        let section = section(2.0, plane);
+        let [a, b, c, d] = plane;
+        let [ca, cb, cc, cd, ce, cf] = section.coefficients;
+        for curve in section.curves().unwrap() {
+            for p in curve.points() {
+                let [x, y, z] = [p.e[0] * 20.0, p.e[1] * 20.0, p.e[2] * 20.0];
+                let size = 1.0 + x * x + y * y + z * z;
+                assert!((a * x + b * y + c * z - d).abs() < 1e-3 * size.sqrt(), "{plane:?}");
+                assert!((4.0 * (x * x + y * y) - z * z).abs() < 1e-3 * size, "{plane:?}");
+
+                // back to plane coordinates, where the 2D equation holds
+                let local = [x - section.origin.e[0], y - section.origin.e[1], z - section.origin.e[2]];
+                let u = local[0] * section.e1.e[0] + local[1] * section.e1.e[1] + local[2] * section.e1.e[2];
+                let v = local[0] * section.e2.e[0] + local[1] * section.e2.e[1] + local[2] * section.e2.e[2];
+                let conic = ca * u * u + cb * u * v + cc * v * v + cd * u + ce * v + cf;
+                assert!(conic.abs() < 1e-3 * size, "{plane:?}");
+                let back = section.to_world(u, v);
+                assert!((back.e[0] - x).abs() < 1e-3 && (back.e[2] - z).abs() < 1e-3);
+            }
+        }
+    }
+}
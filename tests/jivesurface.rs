@@ -0,0 +1,339 @@
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind, SPHERE, ELLIPSOID, HYPERBOLOID, CONE};
+use jive::la::linear_algebra::{Mat4x4, Vec3f};
+
+#[test]
+fn surface_kind_rejects_bad_parameters() {
+    assert!(SurfaceKind::sphere(-1.0).is_err());
+    assert!(SurfaceKind::ellipsoid(1.0, 0.0, 1.0).is_err());
+    assert!(SurfaceKind::cone(f32::NAN, 5.0).is_err());
+    assert!(SurfaceKind::plane(0.0, 0.0, 0.0, 10.0).is_err());
+    assert_eq!(SurfaceKind::sphere(2.0), Ok(SurfaceKind::Sphere { radius: 2.0 }));
+}
+
+#[test]
+fn surface_kind_from_flag() {
+    let sphere = SurfaceKind::from_flag(SPHERE, [1.0, 0.0, 1.0, 0.0, 0.0, -16.0]);
+    assert_eq!(sphere, Ok(SurfaceKind::Sphere { radius: 2.0 }));
+    let cone = SurfaceKind::from_flag(CONE, [2.0, 5.0, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(cone, Ok(SurfaceKind::Cone { steepness: 2.0, height: 5.0 }));
+    assert!(SurfaceKind::from_flag(42, [0.0; 6]).is_err());
+}
+
+// the vertices the flag code drew before SurfaceKind, written out the way it computed them
+#[test]
+fn from_flag_draws_the_old_vertices() {
+    const SCALE: f32 = 1.0 / 20.0;
+    let has_vertex = |surface: &JiveSurface, old: Vec3f| {
+        let transformation = surface.transformation();
+        surface.surface_data().into_iter().any(|mut p| {
+            p *= &transformation;
+            p.distance(&old) < 1e-6
+        })
+    };
+    let (theta, psi) = (30.0f32.to_radians(), 8.0f32.to_radians());
+
+    // sphere, d = sqrt|F| / 2
+    let sphere = JiveSurface::from_flag(SPHERE, [1.0, 0.0, 1.0, 0.0, 0.0, -16.0]).unwrap();
+    let d = 2.0;
+    let old = Vec3f::from(d * SCALE * psi.cos() * theta.sin(), d * SCALE * psi.sin() * theta.sin(), d * SCALE * theta.cos());
+    assert!(has_vertex(&sphere, old));
+
+    // ellipsoid, a = A / 2, b = C / 2, c = min(a, b)
+    let ellipsoid = JiveSurface::from_flag(ELLIPSOID, [4.0, 0.0, 2.0, 0.0, 0.0, -9.0]).unwrap();
+    let (a, b, c, d) = (2.0, 1.0, 1.0, 1.5);
+    let old = Vec3f::from(a * d * SCALE * psi.cos() * theta.sin(), b * d * SCALE * psi.sin() * theta.sin(), c * d * SCALE * theta.cos());
+    assert!(has_vertex(&ellipsoid, old));
+
+    // hyperboloid, v = 5 so t = 0.5, returned as (x, z, y)
+    let hyperboloid = JiveSurface::from_flag(HYPERBOLOID, [3.0, 0.0, 2.0, 0.0, 0.0, -1.0]).unwrap();
+    let (a, b, t, theta) = (3.0, 2.0, 0.5f32, 40.0f32.to_radians());
+    let x = SCALE * SCALE * a * t.cosh() * theta.cos();
+    let y = SCALE * SCALE * b * t.cosh() * theta.sin();
+    let z = SCALE * t.sinh();
+    assert!(has_vertex(&hyperboloid, Vec3f::from(x, z, y)));
+    // only the upper half
+    assert!(hyperboloid.surface_data().iter().all(|p| p.e[2] >= 0.0));
+}
+
+#[test]
+fn sphere_points_lie_on_the_sphere() {
+    let sphere = JiveSurface::new(SurfaceKind::sphere(10.0).unwrap()).unwrap();
+    for p in sphere.surface_data() {
+        assert!((p.magnitude() - 0.5).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn builder_sets_ranges_and_resolution() {
+    let bowl = JiveSurface::builder(SurfaceKind::paraboloid(1.0, 1.0).unwrap())
+        .v_range(0.0, 2.5)
+        .samples(720, 5)
+        .build()
+        .unwrap();
+    // u wraps so 720 columns, v does not so 6 rows
+    assert_eq!(bowl.surface_data().len(), 720 * 6);
+    assert!(bowl.surface_data().iter().all(|p| p.e[2] <= 2.5 * 2.5 / 20.0 + 1e-6));
+
+    assert!(JiveSurface::builder(SurfaceKind::sphere(1.0).unwrap()).v_range(3.0, 1.0).build().is_err());
+    assert!(JiveSurface::builder(SurfaceKind::sphere(1.0).unwrap()).samples(0, 10).build().is_err());
+}
+
+#[test]
+fn edge_length_picks_sample_counts() {
+    // radius 10 -> 0.5 after scaling, so the equator is pi long and a meridian pi / 2
+    let sphere = JiveSurface::builder(SurfaceKind::sphere(10.0).unwrap())
+        .edge_length(0.1)
+        .build()
+        .unwrap();
+    let sampling = sphere.sampling();
+    assert!((31..=33).contains(&sampling.u_samples), "{:?}", sampling);
+    assert!((15..=17).contains(&sampling.v_samples), "{:?}", sampling);
+}
+
+// the implicit equation of each shape, in the unscaled -10,10 space
+fn on_surface(kind: &SurfaceKind, p: [f32; 3]) -> f32 {
+    let [x, y, z] = [p[0] * 20.0, p[1] * 20.0, p[2] * 20.0];
+    match *kind {
+        SurfaceKind::Torus { major_radius, minor_radius } =>
+            ((x * x + y * y).sqrt() - major_radius).powi(2) + z * z - minor_radius * minor_radius,
+        SurfaceKind::Cylinder { a, b, .. } => x * x / (a * a) + y * y / (b * b) - 1.0,
+        SurfaceKind::HyperboloidTwoSheets { a, b, c } => z * z / (c * c) - x * x / (a * a) - y * y / (b * b) - 1.0,
+        SurfaceKind::HyperbolicParaboloid { a, b } => x * x / (a * a) - y * y / (b * b) - z,
+        SurfaceKind::EllipticCone { a, b, .. } => x * x / (a * a) + y * y / (b * b) - z * z,
+        SurfaceKind::Hyperboloid { a, b, c } => x * x / (a * a) + y * y / (b * b) - z * z / (c * c) - 1.0,
+        SurfaceKind::Plane { a, b, c, d, .. } => a * x + b * y + c * z - d,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn new_standard_surfaces_satisfy_their_equations() {
+    let kinds = [
+        SurfaceKind::torus(6.0, 2.0).unwrap(),
+        SurfaceKind::cylinder(3.0, 10.0).unwrap(),
+        SurfaceKind::elliptic_cylinder(3.0, 5.0, 10.0).unwrap(),
+        SurfaceKind::hyperboloid_two_sheets(2.0, 3.0, 1.5).unwrap(),
+        SurfaceKind::hyperbolic_paraboloid(2.0, 1.0).unwrap(),
+        SurfaceKind::elliptic_cone(1.0, 0.5, 8.0).unwrap(),
+        SurfaceKind::hyperboloid(2.0, 2.0, 1.0).unwrap(),
+        SurfaceKind::plane(1.0, 0.0, 0.0, 3.0).unwrap(),
+        SurfaceKind::plane(0.0, 2.0, 0.0, -4.0).unwrap(),
+        SurfaceKind::plane(1.0, 2.0, 0.0, 5.0).unwrap(),
+        SurfaceKind::plane(1.0, -2.0, 2.0, 6.0).unwrap(),
+    ];
+    for kind in kinds.iter() {
+        let surface = JiveSurface::new(*kind).unwrap();
+        for p in surface.surface_data() {
+            let scale = 1.0 + (p.e[0] * p.e[0] + p.e[1] * p.e[1] + p.e[2] * p.e[2]) * 400.0;
+            assert!(on_surface(kind, p.e).abs() < 1e-4 * scale, "{:?} {:?}", kind, p);
+        }
+    }
+}
+
+#[test]
+fn both_halves_are_drawn() {
+    let below = |kind: SurfaceKind| JiveSurface::new(kind).unwrap().surface_data().iter().any(|p| p.e[2] < -1e-3);
+    let above = |kind: SurfaceKind| JiveSurface::new(kind).unwrap().surface_data().iter().any(|p| p.e[2] > 1e-3);
+
+    let two_sheets = SurfaceKind::hyperboloid_two_sheets(1.0, 1.0, 1.0).unwrap();
+    assert_eq!(JiveSurface::new(two_sheets).unwrap().patches().count(), 2);
+    assert!(below(two_sheets) && above(two_sheets));
+
+    let double_cone = SurfaceKind::elliptic_cone(1.0, 1.0, 5.0).unwrap();
+    assert!(below(double_cone) && above(double_cone));
+
+    let hyperboloid = SurfaceKind::hyperboloid(1.0, 1.0, 1.0).unwrap();
+    assert!(below(hyperboloid) && above(hyperboloid));
+}
+
+#[test]
+fn analytic_partials_match_finite_differences() {
+    use jive::jives::jiveparametric::{ParametricSurface, finite_difference_partials};
+    let kinds = [
+        SurfaceKind::sphere(4.0).unwrap(),
+        SurfaceKind::ellipsoid(2.0, 3.0, 4.0).unwrap(),
+        SurfaceKind::hyperboloid(1.0, 2.0, 3.0).unwrap(),
+        SurfaceKind::hyperboloid_two_sheets(1.0, 2.0, 3.0).unwrap(),
+        SurfaceKind::paraboloid(1.0, 2.0).unwrap(),
+        SurfaceKind::hyperbolic_paraboloid(1.0, 2.0).unwrap(),
+        SurfaceKind::plane(1.0, 2.0, 3.0, 4.0).unwrap(),
+        SurfaceKind::cone(2.0, 5.0).unwrap(),
+        SurfaceKind::elliptic_cone(1.0, 2.0, 5.0).unwrap(),
+        SurfaceKind::elliptic_cylinder(1.0, 2.0, 5.0).unwrap(),
+        SurfaceKind::torus(5.0, 2.0).unwrap(),
+    ];
+    for kind in kinds.iter() {
+        let (umin, umax) = kind.u_domain();
+        let (vmin, vmax) = kind.v_domain();
+        for (s, t) in [(0.3, 0.4), (0.7, 0.55), (0.15, 0.8)] {
+            let (u, v) = (umin + (umax - umin) * s, vmin + (vmax - vmin) * t);
+            let (du, dv) = kind.partials(u, v);
+            let (fu, fv) = finite_difference_partials(kind, u, v);
+            for (exact, approx) in [(du, fu), (dv, fv)] {
+                let scale = exact.magnitude().max(1e-3);
+                for k in 0..3 {
+                    assert!((exact.e[k] - approx.e[k]).abs() < 1e-2 * scale, "{kind:?} at ({u}, {v})");
+                }
+            }
+            let n = kind.normal(u, v);
+            assert!((n.magnitude() - 1.0).abs() < 1e-4, "{kind:?}");
+        }
+    }
+}
+
+#[test]
+fn closed_surfaces_have_outward_normals() {
+    let kinds = [
+        SurfaceKind::sphere(4.0).unwrap(),
+        SurfaceKind::ellipsoid(2.0, 3.0, 4.0).unwrap(),
+        SurfaceKind::elliptic_cylinder(1.0, 2.0, 5.0).unwrap(),
+    ];
+    for kind in kinds {
+        let surface = JiveSurface::new(kind).unwrap();
+        let points = surface.surface_data();
+        let normals = surface.surface_normals();
+        assert_eq!(points.len(), normals.len());
+        for (p, n) in points.iter().zip(normals.iter()) {
+            // every point's normal leans away from the axis or center, poles included
+            let radial = if matches!(kind, SurfaceKind::Cylinder { .. }) { [p.e[0], p.e[1], 0.0] } else { p.e };
+            let lean = radial[0] * n.e[0] + radial[1] * n.e[1] + radial[2] * n.e[2];
+            assert!(lean > 0.0, "{kind:?} at {p:?}");
+        }
+    }
+}
+
+#[test]
+fn plane_patch_is_centered_on_the_closest_point() {
+    use jive::jives::jiveparametric::ParametricSurface;
+    let kind = SurfaceKind::plane_with_extent(2.0, -1.0, 2.0, 9.0, 4.0).unwrap();
+    let (center, e1, e2) = kind.plane_basis().unwrap();
+    // d n / |n|^2 = 9 (2, -1, 2) / 9
+    for (got, want) in center.e.iter().zip([2.0, -1.0, 2.0]) {
+        assert!((got - want).abs() < 1e-5);
+    }
+    let dot = |p: [f32; 3], q: [f32; 3]| p[0] * q[0] + p[1] * q[1] + p[2] * q[2];
+    assert!((dot(e1.e, e1.e) - 1.0).abs() < 1e-5 && (dot(e2.e, e2.e) - 1.0).abs() < 1e-5);
+    assert!(dot(e1.e, e2.e).abs() < 1e-5 && dot(e1.e, [2.0, -1.0, 2.0]).abs() < 1e-5);
+
+    // the samples are symmetric about the center and reach exactly extent along e1 and e2
+    let surface = JiveSurface::new(kind).unwrap();
+    let points = surface.surface_data();
+    // the patch works its frame out once and lands on the same points SurfaceKind does
+    let (u, v) = (1.5, -2.5);
+    assert!(surface.parametric().unwrap().evaluate(u, v).distance(&kind.evaluate(u, v)) < 1e-7);
+    let mut sum = [0.0f32; 3];
+    let mut reach = 0.0f32;
+    for p in points.iter() {
+        let local = [p.e[0] * 20.0 - 2.0, p.e[1] * 20.0 + 1.0, p.e[2] * 20.0 - 2.0];
+        for k in 0..3 {
+            sum[k] += local[k];
+        }
+        reach = reach.max(dot(local, e1.e).abs()).max(dot(local, e2.e).abs());
+    }
+    assert!(sum.iter().all(|s| (s / points.len() as f32).abs() < 1e-4));
+    assert!((reach - 4.0).abs() < 1e-4);
+
+    assert!(SurfaceKind::plane_with_extent(1.0, 0.0, 0.0, 0.0, 0.0).is_err());
+    assert!(SurfaceKind::plane(0.0, 0.0, 0.0, 1.0).is_err());
+    let degenerate = SurfaceKind::Plane { a: 0.0, b: 0.0, c: 0.0, d: 1.0, extent: 5.0 };
+    assert!(degenerate.plane_basis().is_err());
+    assert!(JiveSurface::builder(degenerate).build().is_err());
+    assert!(JiveSurface::new(degenerate).is_err());
+}
+
+#[test]
+fn translate_moves_the_placement_only() {
+    let mut surface = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap();
+    let before = surface.surface_data();
+    surface.translate(Vec3f::from(0.25, 0.0, -0.5));
+    surface.translate(Vec3f::from(0.25, 0.0, 0.0));
+    assert_eq!(surface.surface_data(), before);
+    assert_eq!(surface.surface_placement, Mat4x4::translation(&Vec3f::from(0.5, 0.0, -0.5)));
+}
+
+#[test]
+fn isolines_follow_the_sampling() {
+    use jive::jives::jiveparametric::{isolines, FnSurface, Sampling};
+    // a flat sheet whose points are their own parameters
+    let sheet = FnSurface::new(|u, v| Vec3f::from(u, v, 0.0), (0.0, 4.0), (-1.0, 1.0));
+    let sampling = Sampling{ u_range: (1.0, 3.0), v_range: (-1.0, 1.0), u_samples: 8, v_samples: 5 };
+    let lines = isolines(&sheet, &sampling, 3, 2);
+    assert_eq!(lines.len(), 5);
+    // u = 1, 2, 3 run across the v range in v_samples steps, both ends included
+    for (line, u) in lines[..3].iter().zip([1.0, 2.0, 3.0]) {
+        assert_eq!(line.len(), 6);
+        assert_eq!(line.first(), Some(&Vec3f::from(u, -1.0, 0.0)));
+        assert_eq!(line.last(), Some(&Vec3f::from(u, 1.0, 0.0)));
+    }
+    for (line, v) in lines[3..].iter().zip([-1.0, 1.0]) {
+        assert_eq!(line.len(), 9);
+        assert_eq!(line.first(), Some(&Vec3f::from(1.0, v, 0.0)));
+        assert_eq!(line.last(), Some(&Vec3f::from(3.0, v, 0.0)));
+    }
+}
+
+#[test]
+fn periodic_isolines_close_without_doubling_up() {
+    let torus = JiveSurface::builder(SurfaceKind::torus(6.0, 2.0).unwrap()).samples(36, 18).build().unwrap();
+    let lines = torus.isolines(6, 4);
+    assert_eq!(lines.len(), 10);
+    for line in lines.iter() {
+        // round the whole way and back to the start
+        assert!(line.first().unwrap().distance(line.last().unwrap()) < 1e-5);
+    }
+    // u = 360 would be u = 0 again, so the lines stop one step short of it
+    let starts: Vec<Vec3f> = lines[..6].iter().map(|line| line[0]).collect();
+    for (i, a) in starts.iter().enumerate() {
+        assert!(starts[i + 1..].iter().all(|b| a.distance(b) > 1e-3));
+    }
+}
+
+#[test]
+fn isolines_skip_the_poles() {
+    let sphere = JiveSurface::builder(SurfaceKind::sphere(10.0).unwrap()).samples(24, 12).build().unwrap();
+    // v = 0, 45, 90, 135 and 180 degrees, the first and last are the poles
+    let lines = sphere.isolines(4, 5);
+    assert_eq!(lines.len(), 4 + 3);
+    for line in lines.iter() {
+        let first = line[0];
+        assert!(line.iter().any(|p| p.distance(&first) > 1e-3));
+    }
+    // the meridians still run pole to pole
+    assert!(lines[0][0].distance(&Vec3f::from(0.0, 0.0, 0.5)) < 1e-5);
+    assert!(lines[0].last().unwrap().distance(&Vec3f::from(0.0, 0.0, -0.5)) < 1e-5);
+
+    let ellipsoid = JiveSurface::new(SurfaceKind::ellipsoid(2.0, 3.0, 4.0).unwrap()).unwrap();
+    assert_eq!(ellipsoid.isolines(6, 3).len(), 6 + 1);
+}
+
+#[test]
+fn surface_data_samples_points_only() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use jive::jives::jiveparametric::ParametricSurface;
+
+    // a flat sheet that counts how often it is asked for normals
+    struct Counted(Rc<Cell<usize>>);
+    impl ParametricSurface for Counted {
+        fn evaluate(&self, u: f32, v: f32) -> Vec3f {
+            Vec3f::from(u, v, 0.0)
+        }
+        fn u_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+        fn v_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+        fn normal(&self, _u: f32, _v: f32) -> Vec3f {
+            self.0.set(self.0.get() + 1);
+            Vec3f::from(0.0, 0.0, 1.0)
+        }
+    }
+
+    let calls = Rc::new(Cell::new(0));
+    let sheet = JiveSurface::from_parametric(Counted(calls.clone()), 4, 3);
+    assert_eq!(sheet.surface_data().len(), 5 * 4);
+    assert_eq!(calls.get(), 0);
+    assert_eq!(sheet.surface_normals().len(), 5 * 4);
+    assert_eq!(calls.get(), 5 * 4);
+}
@@ -0,0 +1,95 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{render_jive_shaded, ScreenData, ShadingOptions};
+use jive::util::depth::DepthSorted;
+use jive::util::framebuffer::Framebuffer;
+use jive::util::lighting::{shade, Light, Material, Shading};
+
+const BACKGROUND: Color = Color::RGB(0, 0, 0);
+
+fn matte(base_color: Color) -> Material {
+    Material{ base_color, diffuse: 0.5, specular: 0.0, shininess: 1.0 }
+}
+
+#[test]
+fn lights_add_up() {
+    let eye = Vec3f::from(0.0, 0.0, -2.0);
+    let (at, facing_eye) = (Vec3f::zero(), Vec3f::from(0.0, 0.0, -1.0));
+    let material = matte(Color::RGB(200, 100, 50));
+
+    assert_eq!(shade(&material, &[Light::ambient(0.5)], &at, &facing_eye, &eye), Color::RGB(100, 50, 25));
+    // head on, from the eye's side
+    let head_on = Light::directional(Vec3f::from(0.0, 0.0, 1.0), 1.0).unwrap();
+    assert_eq!(shade(&material, &[head_on], &at, &facing_eye, &eye), Color::RGB(100, 50, 25));
+    // a point light straight in front is the same thing
+    assert_eq!(shade(&material, &[Light::point(Vec3f::from(0.0, 0.0, -5.0), 1.0)], &at, &facing_eye, &eye), Color::RGB(100, 50, 25));
+    // at 60 degrees half as much
+    let slanted = Light::directional(Vec3f::from(3.0f32.sqrt(), 0.0, 1.0), 1.0).unwrap();
+    assert_eq!(shade(&material, &[slanted], &at, &facing_eye, &eye), Color::RGB(50, 25, 13));
+    // lit from behind, only the ambient is left
+    let behind = Light::directional(Vec3f::from(0.0, 0.0, -1.0), 1.0).unwrap();
+    assert_eq!(shade(&material, &[behind, Light::ambient(0.1)], &at, &facing_eye, &eye), Color::RGB(20, 10, 5));
+    // too much light stops at full
+    assert_eq!(shade(&material, &[Light::ambient(9.0)], &at, &facing_eye, &eye), Color::RGB(255, 255, 255));
+    assert!(Light::directional(Vec3f::zero(), 1.0).is_err());
+}
+
+#[test]
+fn both_sides_are_lit_and_highlights_are_white() {
+    let eye = Vec3f::from(0.0, 0.0, -2.0);
+    let light = [Light::directional(Vec3f::from(0.3, 0.2, 1.0), 1.0).unwrap()];
+    let material = Material::new(Color::RGB(200, 40, 40));
+    let front = shade(&material, &light, &Vec3f::zero(), &Vec3f::from(0.0, 0.0, -1.0), &eye);
+    let back = shade(&material, &light, &Vec3f::zero(), &Vec3f::from(0.0, 0.0, 1.0), &eye);
+    assert_eq!(front, back);
+
+    // with the light behind the eye the highlight adds the same to every channel
+    let shiny = Material{ specular: 0.5, ..matte(Color::RGB(100, 0, 0)) };
+    let head_on = [Light::directional(Vec3f::from(0.0, 0.0, 1.0), 1.0).unwrap()];
+    assert_eq!(shade(&shiny, &head_on, &Vec3f::zero(), &Vec3f::from(0.0, 0.0, -1.0), &eye), Color::RGB(178, 128, 128));
+}
+
+fn lit_sphere(shading: Shading) -> Framebuffer {
+    let screen_data = ScreenData{ screen_width: 120.0, screen_height: 120.0, ..ScreenData::default() };
+    let sphere = JiveSurface::builder(SurfaceKind::sphere(8.0).unwrap())
+        .samples(24, 12)
+        .material(Material::new(Color::RGB(200, 200, 200)))
+        .build()
+        .unwrap();
+    // lit from the left
+    let options = ShadingOptions{ shading, lights: vec![Light::directional(Vec3f::from(1.0, 0.0, 0.0), 1.0).unwrap()] };
+    let mut framebuffer = Framebuffer::with_depth(120, 120).unwrap();
+    render_jive_shaded(&mut framebuffer, &sphere, &screen_data, &options).unwrap();
+    framebuffer
+}
+
+#[test]
+fn shaded_surfaces_read_as_3d() {
+    assert_eq!(ScreenData::default().camera_position().unwrap(), Vec3f::from(0.0, 0.0, -2.0));
+    for shading in [Shading::Flat, Shading::Gouraud, Shading::Phong] {
+        let framebuffer = lit_sphere(shading);
+        // the sphere is about 30 pixels across, bright on the left and dark past the middle
+        let (left, right) = (framebuffer.pixel(50, 60).unwrap(), framebuffer.pixel(70, 60).unwrap());
+        assert!(left.r > 100 && right.r < 10, "{shading:?}: {left:?} {right:?}");
+        assert!(framebuffer.depth(70, 60).unwrap().is_finite());
+        assert_eq!(framebuffer.pixel(5, 5), Some(BACKGROUND));
+        // only the near side is drawn
+        assert!(framebuffer.depth(60, 60).unwrap() < framebuffer.depth(50, 60).unwrap());
+    }
+    // smooth shading smooths
+    assert_ne!(lit_sphere(Shading::Flat).pixels(), lit_sphere(Shading::Phong).pixels());
+    assert_ne!(lit_sphere(Shading::Gouraud).pixels(), lit_sphere(Shading::Phong).pixels());
+
+    // without per pixel color every triangle is one color, still nearest on top
+    let screen_data = ScreenData{ screen_width: 120.0, screen_height: 120.0, ..ScreenData::default() };
+    let sphere = JiveSurface::new(SurfaceKind::sphere(8.0).unwrap()).unwrap();
+    let mut framebuffer = Framebuffer::new(120, 120).unwrap();
+    let mut sorted = DepthSorted::new(&mut framebuffer);
+    render_jive_shaded(&mut sorted, &sphere, &screen_data, &ShadingOptions::default()).unwrap();
+    sorted.present().unwrap();
+    drop(sorted);
+    assert_ne!(framebuffer.pixel(60, 60), Some(BACKGROUND));
+}
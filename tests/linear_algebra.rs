@@ -0,0 +1,249 @@
+use jive::la::linear_algebra::{Mat3x3, Mat4x4, Vec3f, Vec4f, vec3f_cross, vec3f_dot};
+
+#[test]
+#[allow(clippy::op_ref)]
+fn vec3f_operators() {
+    let a = Vec3f::from(1.0, 2.0, 3.0);
+    let b = Vec3f::from(-2.0, 0.5, 4.0);
+    assert_eq!(a + b, Vec3f::from(-1.0, 2.5, 7.0));
+    assert_eq!(&a + &b, a + b);
+    assert_eq!(a - &b, Vec3f::from(3.0, 1.5, -1.0));
+    assert_eq!(-a, Vec3f::from(-1.0, -2.0, -3.0));
+    assert_eq!(a * 2.0, 2.0 * a);
+    assert_eq!(&a * 2.0, Vec3f::from(2.0, 4.0, 6.0));
+    assert_eq!(b / 0.5, Vec3f::from(-4.0, 1.0, 8.0));
+
+    let mut c = a;
+    c += b;
+    c -= &b;
+    c *= 3.0;
+    c /= 3.0;
+    assert_eq!(c, a);
+    c[1] = 7.0;
+    assert_eq!((c[0], c[1], c.z()), (1.0, 7.0, 3.0));
+}
+
+#[test]
+fn vec3f_products_and_lengths() {
+    let x = Vec3f::from(1.0, 0.0, 0.0);
+    let y = Vec3f::from(0.0, 1.0, 0.0);
+    assert_eq!(x.cross(&y), Vec3f::from(0.0, 0.0, 1.0));
+    assert_eq!(vec3f_cross(&y, &x), Vec3f::from(0.0, 0.0, -1.0));
+    assert_eq!(vec3f_dot(&x, &y), 0.0);
+
+    let v = Vec3f::from(3.0, 4.0, 12.0);
+    assert_eq!(v.length(), 13.0);
+    assert_eq!(v.length_squared(), 169.0);
+    assert_eq!(v.try_normalize(), Some(v / 13.0));
+    assert_eq!(Vec3f::zero().try_normalize(), None);
+    assert_eq!(Vec3f::from(1.0, f32::INFINITY, 0.0).try_normalize(), None);
+
+    assert_eq!(x.lerp(&y, 0.25), Vec3f::from(0.75, 0.25, 0.0));
+    assert_eq!(Vec3f::zero().distance(&v), 13.0);
+    assert_eq!(x.min(&y), Vec3f::zero());
+    assert_eq!(x.max(&y), Vec3f::from(1.0, 1.0, 0.0));
+}
+
+fn close(a: &Vec3f, b: &Vec3f) -> bool {
+    a.distance(b) < 1e-5
+}
+
+#[test]
+fn mat3x3_transform_uses_the_original_components() {
+    let mut v = Vec3f::from(1.0, 2.0, 3.0);
+    v *= &Mat3x3{ e: [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0] };
+    assert_eq!(v, Vec3f::from(14.0, 32.0, 50.0));
+
+    // a quarter turn swaps the last two axes
+    let mut w = Vec3f::from(1.0, 2.0, 3.0);
+    w *= &Mat3x3{ e: [1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 1.0, 0.0] };
+    assert_eq!(w, Vec3f::from(1.0, -3.0, 2.0));
+}
+
+#[test]
+fn mat4x4_transform_treats_vec3f_as_a_point() {
+    // translate by (1, 2, 3) and shear x into y, row vector convention
+    let mut matrix = Mat4x4::new();
+    matrix.e[0] = 1.0; matrix.e[5] = 1.0; matrix.e[10] = 1.0; matrix.e[15] = 1.0;
+    matrix.e[1] = 2.0;
+    matrix.e[12] = 1.0; matrix.e[13] = 2.0; matrix.e[14] = 3.0;
+    let v = Vec4f::point(&Vec3f::from(1.0, 1.0, 1.0)) * &matrix;
+    assert_eq!(v.perspective_divide(), Ok(Vec3f::from(2.0, 5.0, 4.0)));
+
+    // directions are not moved
+    let d = Vec4f::direction(&Vec3f::from(1.0, 1.0, 1.0)) * &matrix;
+    assert_eq!(d, Vec4f::from(1.0, 3.0, 1.0, 0.0));
+}
+
+#[test]
+fn perspective_divide_reports_points_behind_the_camera() {
+    // 90 degree field of view, near 1, far 11: f = 1, q = 1.1
+    let mut projection = Mat4x4::new();
+    projection.e[0] = 1.0; projection.e[5] = 1.0;
+    projection.e[10] = 1.1; projection.e[11] = 1.0; projection.e[14] = -1.1;
+
+    let mut clip = Vec4f::point(&Vec3f::from(0.5, -0.25, 2.0));
+    clip *= &projection;
+    assert_eq!(clip.w(), 2.0);
+    assert!(close(&clip.perspective_divide().unwrap(), &Vec3f::from(0.25, -0.125, 0.55)));
+
+    let level = Vec4f::point(&Vec3f::from(1.0, 1.0, 0.0)) * &projection;
+    assert!(level.perspective_divide().is_err());
+    let behind = Vec4f::point(&Vec3f::from(1.0, 1.0, -3.0)) * &projection;
+    assert!(behind.perspective_divide().is_err());
+
+    // a projection is not just a move, the point only lands right after the divide
+    let far = Vec4f::point(&Vec3f::from(0.5, -0.25, 4.0)) * &projection;
+    assert!(!close(&far.xyz(), &far.perspective_divide().unwrap()));
+    assert!(close(&far.perspective_divide().unwrap(), &Vec3f::from(0.125, -0.0625, 0.825)));
+}
+
+fn close4(a: &Mat4x4, b: &Mat4x4) -> bool {
+    a.e.iter().zip(b.e.iter()).all(|(x, y)| (x - y).abs() < 1e-4)
+}
+
+fn sample_mat4() -> Mat4x4 {
+    Mat4x4{ e: [2.0, 0.0, 1.0, 3.0,
+                1.0, 1.0, 0.0, 2.0,
+                0.0, 4.0, 1.0, 1.0,
+                3.0, 1.0, 2.0, 5.0] }
+}
+
+#[test]
+fn mat4x4_product_transpose_and_determinant() {
+    let a = sample_mat4();
+    let product = a * Mat4x4::identity();
+    assert_eq!(product, a);
+    // first row by hand: (2, 0, 1, 3) times the columns of a
+    let square = a * a;
+    assert_eq!(&square.e[0..4], &[13.0, 7.0, 9.0, 22.0]);
+
+    let mut b = a;
+    b *= &Mat4x4::translation(&Vec3f::from(1.0, 0.0, 0.0));
+    assert_eq!(b, a * Mat4x4::translation(&Vec3f::from(1.0, 0.0, 0.0)));
+
+    assert_eq!(a.transpose().e[1], 1.0);
+    assert_eq!(a.transpose().transpose(), a);
+    // expanded along the first row
+    assert!((a.determinant() - 2.0).abs() < 1e-4);
+    assert!((a.transpose().determinant() - a.determinant()).abs() < 1e-4);
+}
+
+#[test]
+fn mat4x4_inverse() {
+    let a = sample_mat4();
+    let inverse = a.inverse().unwrap();
+    assert!(close4(&(a * inverse), &Mat4x4::identity()));
+    assert!(close4(&(inverse * a), &Mat4x4::identity()));
+
+    let view = Mat4x4::look_at(&Vec3f::from(3.0, -2.0, 5.0), &Vec3f::zero(), &Vec3f::from(0.0, 0.0, 1.0)).unwrap();
+    assert!(close4(&(view * view.inverse().unwrap()), &Mat4x4::identity()));
+
+    let mut flat = a;
+    flat.e[4..8].copy_from_slice(&[4.0, 0.0, 2.0, 6.0]);
+    assert_eq!(flat.determinant(), 0.0);
+    assert!(flat.inverse().is_none());
+}
+
+#[test]
+fn mat4x4_constructors_move_points_as_expected() {
+    let p = Vec4f::point(&Vec3f::from(1.0, 2.0, 3.0));
+
+    let moved = p * &Mat4x4::translation(&Vec3f::from(-1.0, 0.5, 2.0));
+    assert_eq!(moved, Vec4f::from(0.0, 2.5, 5.0, 1.0));
+    assert_eq!(Vec4f::direction(&Vec3f::from(1.0, 2.0, 3.0)) * &Mat4x4::translation(&Vec3f::from(-1.0, 0.5, 2.0)),
+               Vec4f::from(1.0, 2.0, 3.0, 0.0));
+    assert_eq!(p * &Mat4x4::scale(&Vec3f::from(2.0, -1.0, 0.5)), Vec4f::from(2.0, -2.0, 1.5, 1.0));
+
+    // a quarter turn about z takes x to y and y to -x
+    let quarter = Mat4x4::rotation(&Vec3f::from(0.0, 0.0, 2.0), std::f32::consts::FRAC_PI_2).unwrap();
+    assert!(close(&(p * &quarter).xyz(), &Vec3f::from(-2.0, 1.0, 3.0)));
+    // a third of a turn about (1, 1, 1) cycles the axes
+    let third = Mat4x4::rotation(&Vec3f::from(1.0, 1.0, 1.0), 2.0 * std::f32::consts::PI / 3.0).unwrap();
+    assert!(close(&(p * &third).xyz(), &Vec3f::from(3.0, 1.0, 2.0)));
+    assert!(Mat4x4::rotation(&Vec3f::zero(), 1.0).is_err());
+}
+
+#[test]
+fn look_at_puts_the_target_straight_ahead() {
+    let eye = Vec3f::from(0.0, 0.0, -5.0);
+    let view = Mat4x4::look_at(&eye, &Vec3f::zero(), &Vec3f::from(0.0, 1.0, 0.0)).unwrap();
+    // looking down +z from -5 is just a move
+    assert!(close4(&view, &Mat4x4::translation(&Vec3f::from(0.0, 0.0, 5.0))));
+
+    let eye = Vec3f::from(4.0, 4.0, 2.0);
+    let target = Vec3f::from(1.0, 0.0, 2.0);
+    let view = Mat4x4::look_at(&eye, &target, &Vec3f::from(0.0, 0.0, 1.0)).unwrap();
+    assert!(close(&(Vec4f::point(&eye) * &view).xyz(), &Vec3f::zero()));
+    assert!(close(&(Vec4f::point(&target) * &view).xyz(), &Vec3f::from(0.0, 0.0, 5.0)));
+    // up stays up
+    let above = Vec4f::point(&(eye + Vec3f::from(0.0, 0.0, 1.0))) * &view;
+    assert!(close(&above.xyz(), &Vec3f::from(0.0, 1.0, 0.0)));
+
+    assert!(Mat4x4::look_at(&eye, &eye, &Vec3f::from(0.0, 0.0, 1.0)).is_err());
+    assert!(Mat4x4::look_at(&eye, &target, &Vec3f::from(-3.0, -4.0, 0.0)).is_err());
+}
+
+#[test]
+fn orthographic_maps_the_box_onto_the_unit_ranges() {
+    let ortho = Mat4x4::orthographic(-4.0, 2.0, -1.0, 3.0, 1.0, 11.0).unwrap();
+    let low = Vec4f::point(&Vec3f::from(-4.0, -1.0, 1.0)) * &ortho;
+    let high = Vec4f::point(&Vec3f::from(2.0, 3.0, 11.0)) * &ortho;
+    assert!(close(&low.perspective_divide().unwrap(), &Vec3f::from(-1.0, -1.0, 0.0)));
+    assert!(close(&high.perspective_divide().unwrap(), &Vec3f::from(1.0, 1.0, 1.0)));
+    assert!(Mat4x4::orthographic(1.0, 1.0, -1.0, 1.0, 0.0, 1.0).is_err());
+}
+
+fn close3(a: &Mat3x3, b: &Mat3x3, tolerance: f32) -> bool {
+    a.e.iter().zip(b.e.iter()).all(|(x, y)| (x - y).abs() < tolerance)
+}
+
+#[test]
+fn mat3x3_algebra() {
+    let m = Mat3x3{ e: [2.0, -1.0, 0.0, 1.0, 3.0, 2.0, 0.0, 1.0, 4.0] };
+    // 2 (12 - 2) + 1 (4 - 0) + 0
+    assert_eq!(m.determinant(), 24.0);
+    assert_eq!(m.transpose().e, [2.0, 1.0, 0.0, -1.0, 3.0, 1.0, 0.0, 2.0, 4.0]);
+    assert_eq!(m.transpose().determinant(), m.determinant());
+    assert_eq!(m.adjugate().e, [10.0, 4.0, -2.0, -4.0, 8.0, -4.0, 1.0, -2.0, 7.0]);
+    assert!(close3(&(m * m.adjugate()), &Mat3x3{ e: [24.0, 0.0, 0.0, 0.0, 24.0, 0.0, 0.0, 0.0, 24.0] }, 1e-4));
+
+    let inverse = m.inverse().unwrap();
+    assert!(close3(&(m * inverse), &Mat3x3::identity(), 1e-5));
+    assert!(close3(&(inverse * m), &Mat3x3::identity(), 1e-5));
+
+    let flat = Mat3x3{ e: [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0] };
+    assert_eq!(flat.determinant(), 0.0);
+    assert!(flat.inverse().is_none());
+
+    assert_eq!(flat * Vec3f::from(1.0, 2.0, 3.0), Vec3f::from(14.0, 32.0, 50.0));
+    let mut product = m;
+    product *= &flat;
+    assert_eq!(product, m * flat);
+    assert_eq!((m * flat) * Vec3f::from(1.0, 0.0, -1.0), m * (flat * Vec3f::from(1.0, 0.0, -1.0)));
+}
+
+#[test]
+fn symmetric_eigen_handles_hard_cases() {
+    let cases = [
+        // repeated eigenvalue 1, 1, 4
+        Mat3x3{ e: [2.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 1.0, 2.0] },
+        // already diagonal
+        Mat3x3{ e: [3.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.5] },
+        // very different scales with a tiny coupling
+        Mat3x3{ e: [1.0e4, 1.0e-3, 0.0, 1.0e-3, 1.0e-2, 2.0, 0.0, 2.0, -5.0] },
+        // all zero
+        Mat3x3::new(),
+    ];
+    for m in cases.iter() {
+        let (values, vectors) = m.symmetric_eigen();
+        // columns are orthonormal
+        assert!(close3(&(vectors.transpose() * vectors), &Mat3x3::identity(), 1e-5));
+        let diagonal = Mat3x3{ e: [values[0], 0.0, 0.0, 0.0, values[1], 0.0, 0.0, 0.0, values[2]] };
+        let scale = m.e.iter().fold(1.0f32, |a, b| a.max(b.abs()));
+        assert!(close3(&(vectors * diagonal * vectors.transpose()), m, 1e-5 * scale), "{m:?} -> {values:?}");
+    }
+    let (mut values, _) = cases[0].symmetric_eigen();
+    values.sort_by(f32::total_cmp);
+    assert!((values[0] - 1.0).abs() < 1e-5 && (values[1] - 1.0).abs() < 1e-5 && (values[2] - 4.0).abs() < 1e-5);
+}
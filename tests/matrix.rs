@@ -0,0 +1,77 @@
+use jive::la::matrix::{Eigen2x2, Mat2x2};
+
+fn close(p: [f32; 2], q: [f32; 2]) -> bool {
+    (p[0] - q[0]).abs() < 1e-5 && (p[1] - q[1]).abs() < 1e-5
+}
+
+// M v = l v and |v| = 1
+fn is_eigenpair(m: &Mat2x2, l: f32, v: [f32; 2]) -> bool {
+    ((v[0] * v[0] + v[1] * v[1]) - 1.0).abs() < 1e-5 && close(m.apply(v), [l * v[0], l * v[1]])
+}
+
+#[test]
+fn mat2x2_real_eigen_pairs() {
+    let m = Mat2x2::new([1.0, 0.0, 0.0, 4.0]);
+    assert_eq!(m.eigen(), Eigen2x2::Real{ values: [4.0, 1.0], vectors: [[0.0, 1.0], [1.0, 0.0]] });
+
+    let s = 0.5f32.sqrt();
+    let m = Mat2x2::new([2.0, 1.0, 1.0, 2.0]);
+    match m.eigen() {
+        Eigen2x2::Real { values, vectors } => {
+            assert!((values[0] - 3.0).abs() < 1e-6 && (values[1] - 1.0).abs() < 1e-6);
+            assert!(close(vectors[0], [s, s]) && close(vectors[1], [s, -s]));
+        }
+        other => panic!("{other:?}"),
+    }
+
+    // not symmetric, and a tiny eigenvalue next to a big one
+    for m in [Mat2x2::new([3.0, 2.0, -1.0, 0.0]), Mat2x2::new([1.0e4, 1.0, 1.0, 1.0e-3]), Mat2x2::new([0.0, 1.0, 0.0, 0.0])] {
+        match m.eigen() {
+            Eigen2x2::Real { values, vectors } => {
+                assert!(values[0] > values[1]);
+                assert!(is_eigenpair(&m, values[0], vectors[0]) && is_eigenpair(&m, values[1], vectors[1]), "{m:?}");
+            }
+            Eigen2x2::Repeated { value, vector, every_direction } => {
+                assert_eq!(m, Mat2x2::new([0.0, 1.0, 0.0, 0.0]));
+                assert_eq!((value, vector, every_direction), (0.0, [1.0, 0.0], false));
+            }
+            other => panic!("{m:?} gave {other:?}"),
+        }
+    }
+    // det / big keeps the small one accurate, 1e4 * l2 + ... = det
+    if let Eigen2x2::Real { values, .. } = Mat2x2::new([1.0e4, 1.0, 1.0, 1.0e-3]).eigen() {
+        assert!((values[1] - (1.0e-3 - 1.0e-4)).abs() < 1e-7, "{values:?}");
+    }
+}
+
+#[test]
+fn mat2x2_repeated_and_complex() {
+    assert_eq!(Mat2x2::new([3.0, 0.0, 0.0, 3.0]).eigen(),
+               Eigen2x2::Repeated{ value: 3.0, vector: [1.0, 0.0], every_direction: true });
+    assert_eq!(Mat2x2::new([0.0; 4]).eigen(),
+               Eigen2x2::Repeated{ value: 0.0, vector: [1.0, 0.0], every_direction: true });
+    // a shear only keeps the x axis
+    assert_eq!(Mat2x2::new([2.0, 1.0, 0.0, 2.0]).eigen(),
+               Eigen2x2::Repeated{ value: 2.0, vector: [1.0, 0.0], every_direction: false });
+
+    // a quarter turn scaled by 2
+    assert_eq!(Mat2x2::new([0.0, -2.0, 2.0, 0.0]).eigen(), Eigen2x2::Complex{ re: 0.0, im: 2.0 });
+    let (s, c) = 0.3f32.sin_cos();
+    match Mat2x2::new([1.5 * c, -1.5 * s, 1.5 * s, 1.5 * c]).eigen() {
+        Eigen2x2::Complex { re, im } => assert!((re - 1.5 * c).abs() < 1e-6 && (im - 1.5 * s).abs() < 1e-6),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn mat2x2_determinant_inverse_trace() {
+    let m = Mat2x2::new([4.0, 7.0, 2.0, 6.0]);
+    assert_eq!(m.trace(), 10.0);
+    assert_eq!(m.determinant(), 10.0);
+    let inverse = m.inverse().unwrap();
+    let product = m * inverse;
+    assert!(close([product.e[0], product.e[1]], [1.0, 0.0]) && close([product.e[2], product.e[3]], [0.0, 1.0]));
+    assert_eq!(m.transpose().e, [4.0, 2.0, 7.0, 6.0]);
+    assert!(Mat2x2::new([1.0, 2.0, 2.0, 4.0]).inverse().is_none());
+    assert_eq!(Mat2x2::identity() * m, m);
+}
@@ -0,0 +1,96 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::{Mat3x3, Mat4x4, Vec3f, Vec4f};
+use jive::la::quaternion::Quat;
+
+fn close(a: &Vec3f, b: &Vec3f) -> bool {
+    a.distance(b) < 1e-5
+}
+
+fn transformed(mat: &Mat3x3, v: &Vec3f) -> Vec3f {
+    let mut out = *v;
+    out *= mat;
+    out
+}
+
+#[test]
+fn quat_rotations_match_the_matrices() {
+    let p = Vec3f::from(1.0, 2.0, 3.0);
+    let roll = Quat::rotation_x(0.7);
+    assert!(close(&roll.rotate(&p), &transformed(&Mat3x3::roll(0.7), &p)));
+    assert!(close(&transformed(&roll.to_mat3x3(), &p), &roll.rotate(&p)));
+
+    let axis = Vec3f::from(1.0, -2.0, 0.5);
+    let q = Quat::from_axis_angle(&axis, 1.3).unwrap();
+    let row = (Vec4f::point(&p) * &q.to_mat4x4()).xyz();
+    assert!(close(&row, &q.rotate(&p)));
+    assert!(close(&row, &(Vec4f::point(&p) * &Mat4x4::rotation(&axis, 1.3).unwrap()).xyz()));
+    assert!(Quat::from_axis_angle(&Vec3f::zero(), 1.0).is_err());
+
+    // the axis stays put
+    assert!(close(&q.rotate(&axis), &axis));
+}
+
+#[test]
+fn quat_composes_and_inverts() {
+    let p = Vec3f::from(1.0, 0.0, 0.0);
+    // x -> y about z, then y -> z about x
+    let both = Quat::rotation_x(FRAC_PI_2) * Quat::rotation_z(FRAC_PI_2);
+    assert!(close(&both.rotate(&p), &Vec3f::from(0.0, 0.0, 1.0)));
+
+    let q = Quat::from_euler(0.3, -1.1, 2.0);
+    let step = Quat::rotation_z(2.0).rotate(&Quat::rotation_y(-1.1).rotate(&Quat::rotation_x(0.3).rotate(&p)));
+    assert!(close(&q.rotate(&p), &step));
+    assert!(close(&q.conjugate().rotate(&q.rotate(&p)), &p));
+
+    assert_eq!(Quat::new(0.0, 0.0, 0.0, 0.0).normalize(), Quat::identity());
+    assert!((Quat::new(1.0, 2.0, 3.0, 4.0).normalize().length() - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn slerp_turns_at_a_steady_rate() {
+    let start = Quat::identity();
+    let end = Quat::rotation_z(PI * 0.75);
+    let p = Vec3f::from(1.0, 0.0, 0.0);
+    assert_eq!(start.slerp(&end, 0.0), start);
+    assert!(close(&start.slerp(&end, 1.0).rotate(&p), &end.rotate(&p)));
+    let third = start.slerp(&end, 1.0 / 3.0);
+    assert!(close(&third.rotate(&p), &Quat::rotation_z(PI * 0.25).rotate(&p)));
+
+    // -end is the same rotation, the short way round is the same path
+    let flipped = Quat::new(-end.w, -end.x, -end.y, -end.z);
+    assert!(close(&start.slerp(&flipped, 0.5).rotate(&p), &start.slerp(&end, 0.5).rotate(&p)));
+    // nearly equal rotations do not blow up
+    let near = Quat::rotation_z(1e-4);
+    assert!(start.slerp(&near, 0.5).w.is_finite());
+}
+
+#[test]
+fn surface_orientation_stays_orthonormal() {
+    let mut surface = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap();
+    for _ in 0..100_000 {
+        surface.rotate_roll(0.01);
+        surface.rotate_pitch(0.013);
+        surface.rotate_yaw(-0.007);
+    }
+    surface.rotate_about(&Vec3f::from(1.0, 1.0, 0.0), 0.5).unwrap();
+    assert!(surface.rotate_about(&Vec3f::zero(), 0.5).is_err());
+
+    let m = surface.transformation().e;
+    for i in 0..3 {
+        for j in 0..3 {
+            let dot: f32 = (0..3).map(|k| m[i * 3 + k] * m[j * 3 + k]).sum();
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((dot - expected).abs() < 1e-5, "rows {i} and {j} give {dot}");
+        }
+    }
+}
+
+#[test]
+fn rotate_roll_matches_the_roll_matrix() {
+    let mut surface = JiveSurface::new(SurfaceKind::sphere(5.0).unwrap()).unwrap();
+    surface.rotate_roll(0.4);
+    surface.rotate_roll(0.5);
+    let p = Vec3f::from(0.1, 0.2, 0.3);
+    assert!(close(&transformed(&surface.transformation(), &p), &transformed(&Mat3x3::roll(0.9), &p)));
+}
@@ -0,0 +1,93 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::la::linear_algebra::Vec3f;
+use jive::util::bustin::{render_jive_shaded, render_jive_surface, ScreenData, ShadingOptions};
+use jive::util::framebuffer::Framebuffer;
+use jive::util::lighting::{Light, Shading};
+use jive::util::shader::{Checkerboard, Fragment, HeightMap, Normal, Position, Shader, Solid};
+
+const RED: Color = Color::RGB(255, 0, 0);
+const BLUE: Color = Color::RGB(0, 0, 255);
+
+fn at(position: Vec3f) -> Fragment {
+    Fragment{ position, normal: Vec3f::from(0.0, 0.0, -1.0), uv: [0.0, 0.0], depth: 0.5, surface_id: 0 }
+}
+
+#[test]
+fn built_in_shaders() {
+    assert_eq!(Solid(RED).shade(&at(Vec3f::zero())), RED);
+
+    // the direction from the origin, the negative side does not wrap around
+    assert_eq!(Position.shade(&at(Vec3f::from(1.0, 0.0, 0.0))), Color::RGB(255, 128, 128));
+    assert_eq!(Position.shade(&at(Vec3f::from(-3.0, 0.0, 0.0))), Color::RGB(0, 128, 128));
+    assert_eq!(Position.shade(&at(Vec3f::zero())), Color::RGB(128, 128, 128));
+    assert_eq!(Normal.shade(&at(Vec3f::zero())), Color::RGB(128, 128, 0));
+
+    let checkers = Checkerboard{ a: RED, b: BLUE, u_squares: 4, v_squares: 2 };
+    let uv = |u: f32, v: f32| Fragment{ uv: [u, v], ..at(Vec3f::zero()) };
+    assert_eq!(checkers.shade(&uv(0.1, 0.1)), RED);
+    assert_eq!(checkers.shade(&uv(0.3, 0.1)), BLUE);
+    assert_eq!(checkers.shade(&uv(0.3, 0.6)), RED);
+    // past 1 (across a seam) it carries on
+    assert_eq!(checkers.shade(&uv(1.1, 0.1)), RED);
+
+    let height = HeightMap::with_stops(Vec3f::from(0.0, 2.0, 0.0), -1.0, 1.0, vec![Color::RGB(0, 0, 0), Color::RGB(200, 100, 0), Color::RGB(200, 200, 200)]).unwrap();
+    assert_eq!(height.shade(&at(Vec3f::from(5.0, -1.0, 0.0))), Color::RGB(0, 0, 0));
+    assert_eq!(height.shade(&at(Vec3f::from(0.0, -0.5, 0.0))), Color::RGB(100, 50, 0));
+    assert_eq!(height.shade(&at(Vec3f::from(0.0, 0.5, 0.0))), Color::RGB(200, 150, 100));
+    assert_eq!(height.shade(&at(Vec3f::from(0.0, 7.0, 0.0))), Color::RGB(200, 200, 200));
+    // the axis it was given is made unit length
+    assert_eq!(height.axis(), Vec3f::from(0.0, 1.0, 0.0));
+    assert_eq!((height.low(), height.high(), height.stops().len()), (-1.0, 1.0, 3));
+    assert!(HeightMap::new(Vec3f::zero(), 0.0, 1.0).is_err());
+    assert!(HeightMap::new(Vec3f::from(0.0, 0.0, 1.0), 1.0, 1.0).is_err());
+    assert!(HeightMap::with_stops(Vec3f::from(0.0, 0.0, 1.0), 0.0, 1.0, Vec::new()).is_err());
+}
+
+#[test]
+fn shaders_combine() {
+    let fragment = at(Vec3f::zero());
+    assert_eq!(Solid(RED).mix(Solid(BLUE), 0.25).shade(&fragment), Color::RGB(191, 0, 64));
+    assert_eq!(Solid(Color::RGBA(255, 128, 0, 255)).multiply(Solid(Color::RGBA(128, 255, 255, 128))).shade(&fragment),
+               Color::RGBA(128, 128, 0, 128));
+
+    // closures are shaders, here one that picks by surface
+    let by_surface = |fragment: &Fragment| if fragment.surface_id == 1 { RED } else { BLUE };
+    assert_eq!(by_surface.shade(&Fragment{ surface_id: 1, ..fragment }), RED);
+    let tinted = Checkerboard{ a: Color::RGB(255, 255, 255), b: Color::RGB(0, 0, 0), u_squares: 2, v_squares: 2 }.multiply(by_surface);
+    assert_eq!(tinted.shade(&fragment), BLUE);
+}
+
+#[test]
+fn surfaces_render_through_their_shaders() {
+    let screen_data = ScreenData{ screen_width: 120.0, screen_height: 120.0, ..ScreenData::default() };
+    let mut sphere = JiveSurface::builder(SurfaceKind::sphere(8.0).unwrap())
+        .samples(16, 8)
+        .surface_id(7)
+        .build()
+        .unwrap();
+    sphere.set_shader(move |fragment: &Fragment| {
+        assert_eq!(fragment.surface_id, 7);
+        assert!((fragment.normal.length() - 1.0).abs() < 1e-4);
+        assert!(fragment.depth > 0.0 && fragment.depth < 1.0);
+        RED
+    });
+    let mut framebuffer = Framebuffer::with_depth(120, 120).unwrap();
+    render_jive_surface(&mut framebuffer, &sphere, &screen_data).unwrap();
+    assert_eq!(framebuffer.pixel(60, 60), Some(RED));
+
+    // lit, the shader gives the base color: only ambient light leaves it scaled down evenly
+    let options = ShadingOptions{ shading: Shading::Phong, lights: vec![Light::ambient(0.5)] };
+    let checkers = Checkerboard{ a: Color::RGB(200, 0, 0), b: Color::RGB(0, 0, 200), u_squares: 8, v_squares: 4 };
+    let sphere = JiveSurface::builder(SurfaceKind::sphere(8.0).unwrap()).shader(checkers).build().unwrap();
+    let mut framebuffer = Framebuffer::with_depth(120, 120).unwrap();
+    render_jive_shaded(&mut framebuffer, &sphere, &screen_data, &options).unwrap();
+    let mut colors: Vec<Color> = (45..75).flat_map(|x| (45..75).map(move |y| (x, y)))
+        .filter_map(|(x, y)| framebuffer.pixel(x, y))
+        .filter(|c| *c != Color::RGB(0, 0, 0))
+        .collect();
+    colors.sort_by_key(|c| (c.r, c.g, c.b));
+    colors.dedup();
+    assert_eq!(colors, vec![Color::RGB(0, 0, 100), Color::RGB(100, 0, 0)]);
+}
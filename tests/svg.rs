@@ -0,0 +1,59 @@
+use sdl2::pixels::Color;
+
+use jive::jives::jivesurface::{JiveSurface, SurfaceKind};
+use jive::util::backend::RenderBackend;
+use jive::util::bustin::{render_jive_wireframe, ScreenData, WireframeOptions};
+use jive::util::svg::SvgDocument;
+
+#[test]
+fn shapes_become_elements() {
+    assert!(SvgDocument::new(10, 0).is_err());
+    let mut svg = SvgDocument::new(40, 30).unwrap();
+    svg.line((1, 2), (5, 6), 3, Color::RGB(255, 0, 0)).unwrap();
+    svg.filled_triangle((0.0, 0.0), (4.0, 0.0), (0.0, 4.5), Color::RGBA(0, 255, 0, 51)).unwrap();
+    svg.filled_circle((10, 10), 3, Color::RGB(0, 0, 255)).unwrap();
+    svg.text(2, 20, "a<b", Color::RGB(1, 2, 3)).unwrap();
+    assert_eq!(svg.elements(), &[
+        "<line x1=\"1.5\" y1=\"2.5\" x2=\"5.5\" y2=\"6.5\" stroke-width=\"3\" stroke-linecap=\"square\" stroke=\"rgb(255,0,0)\"/>",
+        "<polygon points=\"0,0 4,0 0,4.5\" fill=\"rgb(0,255,0)\" fill-opacity=\"0.200\"/>",
+        "<circle cx=\"10.5\" cy=\"10.5\" r=\"3.5\" fill=\"rgb(0,0,255)\"/>",
+        "<text x=\"2\" y=\"27\" font-family=\"monospace\" font-size=\"8\" textLength=\"24\" lengthAdjust=\"spacingAndGlyphs\" fill=\"rgb(1,2,3)\">a&lt;b</text>",
+    ]);
+
+    let document = svg.to_svg();
+    assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"30\" viewBox=\"0 0 40 30\">"));
+    assert!(document.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn centers_left_of_and_above_the_origin() {
+    // pixel -3 is [-3, -2), its middle is -2.5
+    let mut svg = SvgDocument::new(10, 10).unwrap();
+    svg.line((-3, -1), (4, 0), 1, Color::RGB(255, 0, 0)).unwrap();
+    svg.filled_circle((-1, 2), 2, Color::RGB(0, 0, 255)).unwrap();
+    svg.circle((0, -2), 3, Color::RGB(0, 0, 255)).unwrap();
+    assert_eq!(svg.elements(), &[
+        "<line x1=\"-2.5\" y1=\"-0.5\" x2=\"4.5\" y2=\"0.5\" stroke-width=\"1\" stroke-linecap=\"square\" stroke=\"rgb(255,0,0)\"/>",
+        "<circle cx=\"-0.5\" cy=\"2.5\" r=\"2.5\" fill=\"rgb(0,0,255)\"/>",
+        "<circle cx=\"0.5\" cy=\"-1.5\" r=\"3\" fill=\"none\" stroke-width=\"1\" stroke=\"rgb(0,0,255)\"/>",
+    ]);
+}
+
+#[test]
+fn clear_starts_over() {
+    let mut svg = SvgDocument::new(10, 10).unwrap();
+    svg.point(1, 1, Color::RGB(255, 255, 255)).unwrap();
+    svg.clear(Color::RGB(25, 25, 25)).unwrap();
+    assert!(svg.elements().is_empty());
+    assert!(svg.to_svg().contains("<rect width=\"100%\" height=\"100%\" fill=\"rgb(25,25,25)\"/>"));
+}
+
+#[test]
+fn scenes_draw_the_same_on_any_backend() {
+    let screen_data = ScreenData{ screen_width: 160.0, screen_height: 120.0, ..ScreenData::default() };
+    let surface = JiveSurface::new(SurfaceKind::sphere(8.0).unwrap()).unwrap();
+    let mut svg = SvgDocument::new(160, 120).unwrap();
+    render_jive_wireframe(&mut svg, &surface, &screen_data, &WireframeOptions::default()).unwrap();
+    assert!(svg.elements().len() > 100);
+    assert!(svg.elements().iter().all(|e| e.starts_with("<line ")));
+}